@@ -0,0 +1,242 @@
+use hadlock::{
+    config::{Axis, Command, ExecTime, KeyEffect},
+    ipc::{self, Query, Request, Response},
+    models::Direction,
+};
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    process,
+};
+
+const USAGE: &str = "Usage: hadlockctl <command> [args]
+
+Commands:
+    workspace <n>               Change to workspace n
+    workspace previous          Change to the workspace shown before
+    workspace <next|prev>       Change to the next or previous workspace with windows
+    move-to <n>                 Move focused window to workspace n
+    layout next                 Cycle the layout of the current workspace
+    kill                        Close focused window
+    resize <delta> <axis>       Resize focused window, axis is horizontal or vertical
+    snap <direction>            Snap focused window
+    shift <direction>           Shift focused window
+    focus <next|prev|direction> Focus another window
+    recent                      Focus the most recently focused window
+    swap <direction>            Swap focused window with its neighbour
+    center                      Center focused window
+    reorder                     Reorder windows on the current workspace
+    swap-master                 Swap focused window with master
+    master-count <delta>        Add or remove master windows
+    master-ratio <delta>        Grow or shrink the master area by delta percent
+    monocle                     Toggle monocle on focused window
+    maximize                    Toggle maximize on focused window
+    sticky                      Toggle showing focused window on every workspace
+    minimize                    Minimize focused window
+    restore                     Restore the window minimized last on the current workspace
+    preselect <direction> [n]   Place the next window next to the focused one, taking n percent
+    rotate                      Rotate the split the focused window is in
+    flip <axis>                 Mirror the split the focused window is in
+    term                        Open the configured terminal
+    reload                      Reload the config file
+    scratchpad <name>           Show or hide a scratchpad
+    exec <program> [args...]    Run a program
+    exit                        Exit hadlock
+    query state                 Print the current state
+    query dump                  Print every monitor, workspace and client
+    subscribe                   Print events as they happen, one json line each
+
+Directions: north, north-east, east, south-east, south, south-west, west, north-west";
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let request = match parse_args(&args) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = match request {
+        Request::Subscribe => subscribe(),
+        _ => send(&request).map(|line| {
+            println!("{}", line);
+            if let Ok(Response::Error(_)) = serde_json::from_str::<Response>(&line) {
+                process::exit(1);
+            }
+        }),
+    };
+
+    if let Err(e) = result {
+        eprintln!(
+            "Failed to talk to hadlock at {:?}: {}",
+            ipc::socket_path(),
+            e
+        );
+        process::exit(1);
+    }
+}
+
+fn connect(request: &Request) -> io::Result<BufReader<UnixStream>> {
+    let mut stream = UnixStream::connect(ipc::socket_path())?;
+    let line = serde_json::to_string(request)?;
+    stream.write_all(format!("{}\n", line).as_bytes())?;
+    Ok(BufReader::new(stream))
+}
+
+fn send(request: &Request) -> io::Result<String> {
+    let mut reply = String::new();
+    connect(request)?.read_line(&mut reply)?;
+    Ok(reply.trim_end().to_string())
+}
+
+/// Prints every event line until hadlock closes the connection.
+/// The first line is the reply to the subscription itself and is skipped.
+fn subscribe() -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    for line in connect(&Request::Subscribe)?.lines().skip(1) {
+        writeln!(stdout, "{}", line?)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+fn parse_args(args: &[String]) -> Result<Request, String> {
+    let (cmd, rest) = match args.split_first() {
+        Some((cmd, rest)) => (cmd.as_str(), rest),
+        None => return Err("No command given".into()),
+    };
+
+    let command = |effect| {
+        Ok(Request::Command {
+            effect,
+            workspace: None,
+        })
+    };
+
+    match (cmd, rest) {
+        ("workspace", [which]) if which == "previous" => command(KeyEffect::PreviousWorkspace),
+        ("workspace", [which]) if which == "next" => command(KeyEffect::NextWorkspace),
+        ("workspace", [which]) if which == "prev" => command(KeyEffect::PrevWorkspace),
+        ("workspace", [ws]) => command(KeyEffect::ChangeWorkspace(parse_num(ws)?)),
+        ("move-to", [ws]) => command(KeyEffect::MoveWindowToWorkspace(parse_num(ws)?)),
+        ("layout", [next]) if next == "next" => command(KeyEffect::CirculateLayout),
+        ("kill", []) => command(KeyEffect::Kill),
+        ("resize", [delta, axis]) => command(KeyEffect::Resize(
+            parse_delta(delta)?,
+            axis.parse::<Axis>()?,
+        )),
+        ("snap", [direction]) => command(KeyEffect::Snap(direction.parse::<Direction>()?)),
+        ("shift", [direction]) => command(KeyEffect::ShiftWindow(direction.parse::<Direction>()?)),
+        ("focus", [which]) if which == "next" => command(KeyEffect::FocusNext),
+        ("focus", [which]) if which == "prev" => command(KeyEffect::FocusPrev),
+        ("focus", [direction]) => command(KeyEffect::Focus(direction.parse::<Direction>()?)),
+        ("recent", []) => command(KeyEffect::CycleRecent),
+        ("swap", [direction]) => command(KeyEffect::SwapWindow(direction.parse::<Direction>()?)),
+        ("center", []) => command(KeyEffect::Center),
+        ("reorder", []) => command(KeyEffect::Reorder),
+        ("swap-master", []) => command(KeyEffect::SwapMaster),
+        ("master-count", [delta]) => command(KeyEffect::IncMasterCount(parse_delta(delta)?)),
+        ("master-ratio", [delta]) => command(KeyEffect::IncMasterRatio(parse_delta(delta)?)),
+        ("monocle", []) => command(KeyEffect::ToggleMonocle),
+        ("maximize", []) => command(KeyEffect::ToggleMaximize),
+        ("sticky", []) => command(KeyEffect::ToggleSticky),
+        ("minimize", []) => command(KeyEffect::Minimize),
+        ("restore", []) => command(KeyEffect::RestoreMinimized),
+        ("preselect", [direction]) => {
+            command(KeyEffect::Preselect(direction.parse::<Direction>()?, 50))
+        }
+        ("preselect", [direction, percent]) => command(KeyEffect::Preselect(
+            direction.parse::<Direction>()?,
+            percent
+                .parse::<u32>()
+                .map_err(|_| format!("{} is not a valid percentage", percent))?,
+        )),
+        ("rotate", []) => command(KeyEffect::Rotate),
+        ("flip", [axis]) => command(KeyEffect::Flip(axis.parse::<Axis>()?)),
+        ("term", []) => command(KeyEffect::OpenTerm),
+        ("reload", []) => command(KeyEffect::ReloadConfig),
+        ("scratchpad", [name]) => command(KeyEffect::ToggleScratchpad(name.clone())),
+        ("exec", [program, args @ ..]) => command(KeyEffect::Custom(Command {
+            exec_time: ExecTime::Now,
+            program: program.clone(),
+            args: args.to_vec(),
+        })),
+        ("exit", []) => command(KeyEffect::Exit),
+        ("query", [what]) if what == "state" => Ok(Request::Query(Query::State)),
+        ("query", [what]) if what == "dump" => Ok(Request::Query(Query::Dump)),
+        ("subscribe", []) => Ok(Request::Subscribe),
+        _ => Err(format!("Invalid command: {}", args.join(" "))),
+    }
+}
+
+fn parse_delta(s: &str) -> Result<i32, String> {
+    s.parse::<i32>()
+        .map_err(|_| format!("{} is not a valid delta", s))
+}
+
+/// Workspaces are numbered from 1 like in the config
+fn parse_num(s: &str) -> Result<u32, String> {
+    s.parse::<u32>()
+        .ok()
+        .filter(|num| *num > 0)
+        .ok_or_else(|| format!("{} is not a valid workspace", s))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_args;
+    use hadlock::{
+        config::{Axis, KeyEffect},
+        ipc::Request,
+        models::Direction,
+    };
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_workspace() {
+        assert_eq!(
+            Ok(Request::Command {
+                effect: KeyEffect::ChangeWorkspace(3),
+                workspace: None
+            }),
+            parse_args(&args("workspace 3"))
+        );
+        assert!(parse_args(&args("workspace 0")).is_err())
+    }
+
+    #[test]
+    fn parse_resize() {
+        assert_eq!(
+            Ok(Request::Command {
+                effect: KeyEffect::Resize(10, Axis::Horizontal),
+                workspace: None
+            }),
+            parse_args(&args("resize 10 horizontal"))
+        )
+    }
+
+    #[test]
+    fn parse_direction() {
+        assert_eq!(
+            Ok(Request::Command {
+                effect: KeyEffect::ShiftWindow(Direction::NorthEast),
+                workspace: None
+            }),
+            parse_args(&args("shift north-east"))
+        )
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_args(&args("resize ten horizontal")).is_err());
+        assert!(parse_args(&args("layout")).is_err());
+        assert!(parse_args(&[]).is_err());
+    }
+}
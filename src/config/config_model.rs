@@ -1,4 +1,4 @@
-use super::KeyAction;
+use super::{Axis, KeyAction};
 use crate::layout::LayoutTag;
 use crate::xlibwrapper::util::{
     keysym_lookup::{into_mod, ModMask},
@@ -8,7 +8,7 @@ use serde::{self, de, Deserialize, Deserializer, Serialize};
 use std::collections::BTreeMap;
 use x11_dl::xlib::Mod4Mask;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Config {
     #[serde(rename = "modKey", deserialize_with = "mod_key_deserialize")]
     pub mod_key: ModMask,
@@ -37,6 +37,9 @@ pub struct Config {
     )]
     pub focused_background_color: Color,
 
+    #[serde(rename = "tabTextColor", default = "default_tab_text_color")]
+    pub tab_text_color: Color,
+
     #[serde(rename = "outerGap", default = "default_outer_gap")]
     pub outer_gap: i32,
 
@@ -46,9 +49,28 @@ pub struct Config {
     #[serde(rename = "smartGaps", default = "default_smart_gaps")]
     pub smart_gaps: bool,
 
+    /// Whether the Grid layout adds a column (Horizontal) or a row (Vertical) first
+    #[serde(rename = "gridBias", default = "default_grid_bias")]
+    pub grid_bias: Axis,
+
+    /// Windows in the master area of ColumnMaster
+    #[serde(rename = "masterCount", default = "default_master_count")]
+    pub master_count: usize,
+
+    /// Share of the width given to the master area of ColumnMaster
+    #[serde(rename = "masterRatio", default = "default_master_ratio")]
+    pub master_ratio: f32,
+
     #[serde(rename = "defaultLayout", default = "default_layout")]
     pub default_layout: LayoutTag,
 
+    /// Changing to the current workspace goes back to the previous one instead
+    #[serde(
+        rename = "workspaceBackAndForth",
+        default = "default_workspace_back_and_forth"
+    )]
+    pub workspace_back_and_forth: bool,
+
     #[serde(rename = "keyBindings", default = "default_key_bindings")]
     pub key_bindings: Vec<KeyAction>,
 
@@ -60,6 +82,12 @@ pub struct Config {
 
     #[serde(rename = "commands", default = "default_commands")]
     pub commands: Vec<super::Command>,
+
+    #[serde(rename = "rules", default = "default_rules")]
+    pub rules: Vec<super::AppRule>,
+
+    #[serde(rename = "scratchpads", default = "default_scratchpads")]
+    pub scratchpads: Vec<super::Scratchpad>,
 }
 
 fn mod_key_deserialize<'de, D>(desierializer: D) -> Result<ModMask, D::Error>
@@ -109,6 +137,10 @@ fn default_focused_background_color() -> Color {
     Color::DefaultFocusedBackground
 }
 
+fn default_tab_text_color() -> Color {
+    Color::DefaultTabText
+}
+
 fn default_outer_gap() -> i32 {
     0
 }
@@ -121,6 +153,22 @@ fn default_smart_gaps() -> bool {
     false
 }
 
+fn default_workspace_back_and_forth() -> bool {
+    false
+}
+
+fn default_grid_bias() -> Axis {
+    Axis::Horizontal
+}
+
+fn default_master_count() -> usize {
+    1
+}
+
+fn default_master_ratio() -> f32 {
+    0.5
+}
+
 fn default_layout() -> LayoutTag {
     LayoutTag::Floating
 }
@@ -145,6 +193,14 @@ fn default_commands() -> Vec<super::Command> {
     vec![]
 }
 
+fn default_rules() -> Vec<super::AppRule> {
+    vec![]
+}
+
+fn default_scratchpads() -> Vec<super::Scratchpad> {
+    vec![]
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -156,10 +212,15 @@ impl Default for Config {
             border_color: default_border_color(),
             background_color: default_background_color(),
             focused_background_color: default_focused_background_color(),
+            tab_text_color: default_tab_text_color(),
             outer_gap: default_outer_gap(),
             inner_gap: default_inner_gap(),
             smart_gaps: default_smart_gaps(),
+            grid_bias: default_grid_bias(),
+            master_count: default_master_count(),
+            master_ratio: default_master_ratio(),
             default_layout: default_layout(),
+            workspace_back_and_forth: default_workspace_back_and_forth(),
             key_bindings: default_key_bindings(),
             workspaces: {
                 let mut workspaces: BTreeMap<u8, String> = BTreeMap::new();
@@ -170,6 +231,8 @@ impl Default for Config {
             },
             term: "xterm".to_string(),
             commands: vec![],
+            rules: vec![],
+            scratchpads: vec![],
         }
     }
 }
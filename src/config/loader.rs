@@ -1,16 +1,15 @@
 use super::config_model::Config;
 use std::env;
 use std::fs;
-use std::io::prelude::*;
 use std::path::*;
 
-pub(super) fn load_config() -> Config {
+pub(super) fn config_path() -> Option<PathBuf> {
     let args: Vec<String> = env::args().collect();
 
-    let path = match args.len() {
+    match args.len() {
         2 => {
             debug!("Path to config: {}", args.get(1).unwrap());
-            args.get(1).expect("Get config path")
+            Some(PathBuf::from(args.get(1).expect("Get config path")))
         }
 
         x => {
@@ -18,27 +17,26 @@ pub(super) fn load_config() -> Config {
                 "Wrong number of arguments:{}\nDefault config will be applied",
                 x
             );
-            ""
+            None
         }
-    };
-
-    let path = Path::new(path);
+    }
+}
 
-    if path.exists() && path.is_file() {
-        let mut file =
-            fs::File::open(path).unwrap_or_else(|_| panic!("Failed to open file: {:?}", path));
-        let mut file_content = String::new();
-        file.read_to_string(&mut file_content)
-            .unwrap_or_else(|_| panic!("Failed to read file content: {:?}", path));
-        match ron::de::from_str(&file_content) {
-            Ok(config) => config,
-            Err(err) => {
-                error!("{}", err);
-                Config::default()
-            }
+pub(super) fn load_config(path: Option<&Path>) -> Config {
+    match path {
+        Some(path) if path.exists() && path.is_file() => read_config(path).unwrap_or_else(|err| {
+            error!("{}", err);
+            Config::default()
+        }),
+        _ => {
+            debug!("Path either doesn't exist or is not a file");
+            Config::default()
         }
-    } else {
-        debug!("Path either doesn't exist or is not a file");
-        Config::default()
     }
 }
+
+pub(super) fn read_config(path: &Path) -> Result<Config, String> {
+    let file_content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file content: {:?}, {}", path, e))?;
+    ron::de::from_str(&file_content).map_err(|e| e.to_string())
+}
@@ -1,14 +1,77 @@
 pub mod config_model;
 mod loader;
+mod watcher;
 
-use crate::models::Direction;
+use crate::{
+    models::{window_type::WindowType, Direction},
+    xlibwrapper::xlibmodels::MonitorId,
+};
 use lazy_static::*;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
 
 use config_model::*;
+pub use watcher::ConfigWatcher;
 
 lazy_static! {
-    pub static ref CONFIG: Config = loader::load_config();
+    pub static ref CONFIG: ConfigHandle = ConfigHandle::new(loader::config_path());
+}
+
+/// Derefs to the currently loaded config, which can be swapped out with `reload`.
+/// Every config is leaked with `Box::leak`, so `current` always points at a `'static` config
+/// and references handed out earlier stay valid. Only reloads that change something leak.
+pub struct ConfigHandle {
+    path: Option<PathBuf>,
+    current: AtomicPtr<Config>,
+    generation: AtomicUsize,
+}
+
+impl ConfigHandle {
+    fn new(path: Option<PathBuf>) -> Self {
+        let config = loader::load_config(path.as_deref());
+        Self {
+            path,
+            current: AtomicPtr::new(Box::leak(Box::new(config))),
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Incremented on every successful reload
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Reads the config file again, the current config is kept if it fails to parse.
+    /// Returns whether the config changed.
+    pub fn reload(&self) -> Result<bool, String> {
+        let path = self.path.as_ref().ok_or("No config file to reload")?;
+        let config = loader::read_config(path)?;
+        if config == **self {
+            return Ok(false);
+        }
+        self.current
+            .store(Box::leak(Box::new(config)), Ordering::Release);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        Ok(true)
+    }
+}
+
+impl Deref for ConfigHandle {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        // Only ever set from Box::leak, so never null and never freed
+        unsafe { &*self.current.load(Ordering::Acquire) }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -17,6 +80,18 @@ pub enum Axis {
     Vertical,
 }
 
+impl std::str::FromStr for Axis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "horizontal" => Ok(Axis::Horizontal),
+            "vertical" => Ok(Axis::Vertical),
+            _ => Err(format!("{} is not a valid axis", s)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Key {
     Letter(String),
@@ -29,6 +104,15 @@ pub enum KeyEffect {
     Resize(i32, Axis),
     MoveToWorkspace,
     ChangeCurrentWorkspace,
+    /// Changes to the workspace with this number in `workspaces`, for bindings other than `Number`
+    ChangeWorkspace(u32),
+    /// Moves the focused window to the workspace with this number in `workspaces`
+    MoveWindowToWorkspace(u32),
+    /// The workspace the current monitor showed before
+    PreviousWorkspace,
+    /// The next workspace with windows on the current monitor
+    NextWorkspace,
+    PrevWorkspace,
     CirculateLayout,
     Center,
     Reorder,
@@ -36,9 +120,37 @@ pub enum KeyEffect {
     Kill,
     Exit,
     ShiftWindow(Direction),
+    /// Focuses the next window of the current workspace, oldest first
+    FocusNext,
+    FocusPrev,
+    /// Walks the windows of every workspace, most recently focused first,
+    /// the window reached when `modKey` is released keeps focus
+    CycleRecent,
+    /// Trades places with the nearest window in that direction,
+    /// or moves to the monitor on that side if there is none
+    SwapWindow(Direction),
+    /// Focuses the nearest window in that direction, on the next monitor if there is none
+    Focus(Direction),
     SwapMaster,
     ToggleMonocle,
     ToggleMaximize,
+    /// Keeps the focused window floating on every workspace of its monitor
+    ToggleSticky,
+    /// Hides the focused window until it is restored
+    Minimize,
+    /// Restores the window minimized last on the current workspace
+    RestoreMinimized,
+    /// Where the next window goes in the Bsp layout and the percentage of the space it takes
+    Preselect(Direction, u32),
+    Rotate,
+    Flip(Axis),
+    /// Adds to the number of master windows, negative to remove
+    IncMasterCount(i32),
+    /// Adds percentage points to the share of the master area
+    IncMasterRatio(i32),
+    ReloadConfig,
+    /// Shows or hides the scratchpad with this name
+    ToggleScratchpad(String),
     Custom(Command),
 }
 
@@ -50,6 +162,37 @@ pub struct KeyAction {
     pub effect: KeyEffect,
 }
 
+/// Workspaces are numbered from 1 in the config and tagged from 0
+pub fn workspace_tag(num: u32) -> Option<u32> {
+    num.checked_sub(1)
+}
+
+/// The keys `Key::Number` binds and the workspace tag each of them is for,
+/// workspaces 1 to 9 get their digit and workspace 10 gets 0
+pub fn number_keys(workspaces: &BTreeMap<u8, String>) -> Vec<(String, u32)> {
+    workspaces
+        .keys()
+        .filter_map(|num| match num {
+            1..=9 => Some((num.to_string(), *num as u32 - 1)),
+            10 => Some(("0".to_string(), 9)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Desktop names by workspace tag, workspaces missing from the config are named by their number
+pub fn desktop_names(workspaces: &BTreeMap<u8, String>) -> Vec<String> {
+    let count = workspaces.keys().max().copied().unwrap_or(0);
+    (1..=count)
+        .map(|num| {
+            workspaces
+                .get(&num)
+                .cloned()
+                .unwrap_or_else(|| num.to_string())
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum ExecTime {
     Pre,
@@ -64,3 +207,192 @@ pub struct Command {
     pub program: String,
     pub args: Vec<String>,
 }
+
+/// Decides where a newly mapped window ends up. Only the first matching rule is applied.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AppRule {
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub instance: Option<String>,
+    /// Matches if the window title contains it
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "windowType", default)]
+    pub window_type: Option<WindowType>,
+
+    #[serde(default)]
+    pub monitor: Option<MonitorId>,
+    /// Numbered from 1 like `workspaces`
+    #[serde(default)]
+    pub workspace: Option<u32>,
+    #[serde(default)]
+    pub state: Option<RuleState>,
+    /// Relative to the monitor, only used for floating windows
+    #[serde(default)]
+    pub geometry: Option<RuleGeometry>,
+    #[serde(rename = "skipFocus", default)]
+    pub skip_focus: bool,
+}
+
+/// A floating window summoned and dismissed with `ToggleScratchpad(name)`.
+/// Its window is recognised by `class`, so `program` should give it a class no other window has.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Scratchpad {
+    pub name: String,
+    pub class: String,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Share of the monitor width it covers
+    #[serde(default = "default_scratchpad_share")]
+    pub width: f32,
+    /// Share of the monitor height it covers
+    #[serde(default = "default_scratchpad_share")]
+    pub height: f32,
+}
+
+fn default_scratchpad_share() -> f32 {
+    0.6
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleState {
+    Floating,
+    Tiled,
+    Maximized,
+    Monocle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl AppRule {
+    /// A rule without any criteria never matches
+    pub fn matches(
+        &self,
+        class: Option<&str>,
+        instance: Option<&str>,
+        name: Option<&str>,
+        window_type: &WindowType,
+    ) -> bool {
+        if self.class.is_none()
+            && self.instance.is_none()
+            && self.name.is_none()
+            && self.window_type.is_none()
+        {
+            return false;
+        }
+        let exact = |wanted: &Option<String>, actual: Option<&str>| match wanted {
+            Some(wanted) => actual == Some(wanted.as_str()),
+            None => true,
+        };
+        let name_matches = match (&self.name, name) {
+            (Some(wanted), Some(name)) => name.contains(wanted.as_str()),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let type_matches = match &self.window_type {
+            Some(wanted) => wanted == window_type,
+            None => true,
+        };
+        exact(&self.class, class) && exact(&self.instance, instance) && name_matches && type_matches
+    }
+
+    /// The tag of the workspace the rule puts windows on
+    pub fn workspace_tag(&self) -> Option<u32> {
+        self.workspace.and_then(workspace_tag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{desktop_names, number_keys, AppRule, ConfigHandle};
+    use crate::models::window_type::WindowType;
+    use std::{collections::BTreeMap, fs};
+
+    fn rule(s: &str) -> AppRule {
+        ron::de::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn rule_matches_class_and_name() {
+        let tested = rule(r#"(class: Some("Firefox"), name: Some("Private"), workspace: Some(2))"#);
+        assert!(tested.matches(
+            Some("Firefox"),
+            Some("Navigator"),
+            Some("Mozilla Firefox Private Browsing"),
+            &WindowType::Normal
+        ));
+        assert!(!tested.matches(
+            Some("Firefox"),
+            Some("Navigator"),
+            Some("Mozilla Firefox"),
+            &WindowType::Normal
+        ));
+        assert!(!tested.matches(None, None, Some("Private"), &WindowType::Normal));
+    }
+
+    #[test]
+    fn rule_workspace_is_numbered_from_one() {
+        assert_eq!(Some(1), rule("(workspace: Some(2))").workspace_tag());
+        assert_eq!(None, rule("(workspace: Some(0))").workspace_tag());
+    }
+
+    #[test]
+    fn workspaces_past_nine() {
+        let workspaces = [(1, "web"), (2, "code"), (10, "ten"), (12, "twelve")]
+            .iter()
+            .map(|(num, name)| (*num, name.to_string()))
+            .collect::<BTreeMap<u8, String>>();
+        assert_eq!(
+            vec![
+                ("1".to_string(), 0),
+                ("2".to_string(), 1),
+                ("0".to_string(), 9)
+            ],
+            number_keys(&workspaces)
+        );
+        let names = desktop_names(&workspaces);
+        assert_eq!(12, names.len());
+        assert_eq!(("web", "3", "ten"), (&*names[0], &*names[2], &*names[9]));
+        assert_eq!("twelve", names[11]);
+    }
+
+    #[test]
+    fn empty_rule_never_matches() {
+        assert!(!rule("(monitor: Some(1))").matches(
+            Some("Firefox"),
+            None,
+            None,
+            &WindowType::Normal
+        ));
+    }
+
+    #[test]
+    fn reload_keeps_config_on_error() {
+        let path = std::env::temp_dir().join(format!("hadlock-test-{}.ron", std::process::id()));
+        fs::write(&path, r#"(modKey: "Super", borderWidth: 3)"#).unwrap();
+        let handle = ConfigHandle::new(Some(path.clone()));
+        assert_eq!(3, handle.border_width);
+
+        fs::write(&path, "(modKey: ").unwrap();
+        assert!(handle.reload().is_err());
+        assert_eq!((3, 0), (handle.border_width, handle.generation()));
+
+        fs::write(&path, r#"(modKey: "Super", borderWidth: 5)"#).unwrap();
+        assert_eq!(Ok(true), handle.reload());
+        assert_eq!((5, 1), (handle.border_width, handle.generation()));
+
+        // Saving without changes is not a reload
+        assert_eq!(Ok(false), handle.reload());
+        assert_eq!(1, handle.generation());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    error::Error,
+    io::{ErrorKind, Read, Write},
+    os::unix::{
+        io::{AsRawFd, RawFd},
+        net::UnixStream,
+    },
+    path::Path,
+    sync::mpsc::channel,
+    thread,
+    time::Duration,
+};
+
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// Watches the config file and wakes the event loop up through `fd` when it changes.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    wake: UnixStream,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let (tx, rx) = channel();
+        let mut watcher = watcher(tx, Duration::from_millis(200))?;
+
+        // Most editors replace the file instead of writing to it, which
+        // would end a watch on the file itself, so watch its directory
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        let (wake, mut notifier) = UnixStream::pair()?;
+        wake.set_nonblocking(true)?;
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        thread::spawn(move || {
+            while let Ok(event) = rx.recv() {
+                let changed = match event {
+                    DebouncedEvent::Create(p)
+                    | DebouncedEvent::Write(p)
+                    | DebouncedEvent::Rename(_, p) => p.file_name() == file_name.as_deref(),
+                    _ => false,
+                };
+                if !changed {
+                    continue;
+                }
+                // Saving often takes several events, wait for them to settle and reload once
+                while rx.recv_timeout(SETTLE).is_ok() {}
+                if notifier.write_all(&[1]).is_err() {
+                    break;
+                }
+            }
+        });
+
+        info!("Watching config: {:?}", path);
+        Ok(Self {
+            _watcher: watcher,
+            wake,
+        })
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.wake.as_raw_fd()
+    }
+
+    /// True if the file changed since the last call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        let mut buf = [0u8; 64];
+        loop {
+            match self.wake.read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => changed = true,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        changed
+    }
+}
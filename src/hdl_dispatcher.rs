@@ -1,154 +1,274 @@
 #![allow(unused)]
 use {
+    crate::config::{ConfigWatcher, CONFIG},
     crate::hdl_reactor::HdlReactor,
+    crate::ipc::{Event, IpcServer, Query, Request, Response, StateDump, StateSummary},
     crate::state::State,
     crate::xlibwrapper::DisplayServer,
     crate::xlibwrapper::{action, xlibmodels::*},
+    nix::poll::{poll, PollFd, PollFlags},
     reducer::*,
+    std::os::unix::io::RawFd,
     std::rc::Rc,
     std::sync::mpsc::*,
     x11_dl::xlib,
 };
 
+type HdlStore = Store<State, HdlReactor>;
+
 pub fn run(xlib: Box<Rc<dyn DisplayServer>>, sender: Sender<bool>) {
     let state = State::new(xlib.clone());
-    let mut store = Store::new(state.clone(), HdlReactor::new(xlib.clone(), state));
+    let (event_tx, event_rx) = channel::<Event>();
+    let mut store = Store::new(
+        state.clone(),
+        HdlReactor::new(xlib.clone(), state, event_tx),
+    );
 
     xlib.grab_server();
-    let _ = xlib.get_top_level_windows().iter().map(|w| {
-        store.dispatch(action::MapRequest {
-            win: *w,
-            parent: xlib.get_root(),
+    // Windows left by a previous session, unmapped ones are not ours to show
+    xlib.get_top_level_windows()
+        .into_iter()
+        .filter(|w| {
+            let attrs = xlib.get_window_attributes(*w);
+            attrs.map_state == xlib::IsViewable && !attrs.override_redirect
+        })
+        .for_each(|w| {
+            store.dispatch(action::MapRequest {
+                win: w,
+                parent: xlib.get_root(),
+            });
         });
-    });
     xlib.ungrab_server();
     let _ = sender.send(true);
 
+    let mut ipc = match IpcServer::bind() {
+        Ok(ipc) => Some(ipc),
+        Err(e) => {
+            warn!("Failed to open ipc socket: {}", e);
+            None
+        }
+    };
+
+    let mut config_watcher = CONFIG
+        .path()
+        .and_then(|path| match ConfigWatcher::new(path) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Failed to watch config: {}", e);
+                None
+            }
+        });
+
     loop {
-        let xevent = xlib.next_event();
-        // debug!("Event: {:?}", xevent);
-        match xevent.get_type() {
-            xlib::ConfigureRequest => {
-                let event = xlib::XConfigureRequestEvent::from(xevent);
-                let window_changes = WindowChanges {
-                    x: event.x,
-                    y: event.y,
-                    width: event.width,
-                    height: event.height,
-                    border_width: event.border_width,
-                    sibling: event.above,
-                    stack_mode: event.detail,
+        while xlib.pending_events() > 0 {
+            let xevent = xlib.next_event();
+            handle_xevent(&mut store, xevent);
+        }
+
+        if config_watcher.as_mut().is_some_and(ConfigWatcher::changed) {
+            store.dispatch(action::ReloadConfig);
+        }
+
+        if let Some(ipc) = ipc.as_mut() {
+            for (client, request) in ipc.poll_requests() {
+                let response = match request {
+                    Ok(Request::Subscribe) => {
+                        ipc.subscribe(client);
+                        Response::Ok
+                    }
+                    Ok(request) => handle_request(&mut store, request),
+                    Err(e) => Response::Error(e),
                 };
-                store.dispatch(action::ConfigurationRequest {
-                    win: event.window,
-                    win_changes: window_changes,
-                    value_mask: event.value_mask,
-                    parent: event.parent,
-                });
-            }
-            xlib::MapRequest => {
-                let event = xlib::XMapRequestEvent::from(xevent);
-                /*debug!(
-                "window type: {}",
-                xlib.get_window_type(event.window).get_name()
-                );*/
-                store.dispatch(action::MapRequest {
-                    win: event.window,
-                    parent: event.parent,
-                });
-            }
-            xlib::UnmapNotify => {
-                let event = xlib::XUnmapEvent::from(xevent);
-                store.dispatch(action::UnmapNotify { win: event.window });
-            }
-            xlib::ButtonPress => {
-                let event = xlib::XButtonEvent::from(xevent);
-                store.dispatch(action::ButtonPress {
-                    win: event.window,
-                    sub_win: event.subwindow,
-                    button: event.button,
-                    x_root: event.x_root as u32,
-                    y_root: event.y_root as u32,
-                    state: event.state as u32,
-                });
+                ipc.reply(client, &response);
             }
-            xlib::ButtonRelease => {
-                let event = xlib::XButtonEvent::from(xevent);
-                store.dispatch(action::ButtonRelease {
-                    win: event.window,
-                    sub_win: event.subwindow,
-                    button: event.button,
-                    x_root: event.x_root as u32,
-                    y_root: event.y_root as u32,
-                    state: event.state as u32,
-                });
-            }
-            xlib::KeyPress => {
-                let event = xlib::XKeyEvent::from(xevent);
-                store.dispatch(action::KeyPress {
-                    win: event.window,
-                    state: event.state,
-                    keycode: event.keycode,
-                });
+            event_rx.try_iter().for_each(|event| ipc.broadcast(&event));
+            ipc.flush();
+        } else {
+            event_rx.try_iter().for_each(drop);
+        }
+
+        // Handling a request may have queued up new events
+        if xlib.pending_events() > 0 {
+            continue;
+        }
+
+        let mut fds = vec![xlib.connection_fd()];
+        let mut writable_fds = vec![];
+        if let Some(config_watcher) = config_watcher.as_ref() {
+            fds.push(config_watcher.fd());
+        }
+        if let Some(ipc) = ipc.as_ref() {
+            fds.extend(ipc.fds());
+            writable_fds = ipc.pending_fds();
+        }
+        wait_for_input(&fds, &writable_fds);
+    }
+}
+
+/// Also wakes up when one of `writable_fds` can take more output
+fn wait_for_input(fds: &[RawFd], writable_fds: &[RawFd]) {
+    let mut poll_fds = fds
+        .iter()
+        .map(|fd| PollFd::new(*fd, PollFlags::POLLIN))
+        .chain(
+            writable_fds
+                .iter()
+                .map(|fd| PollFd::new(*fd, PollFlags::POLLOUT)),
+        )
+        .collect::<Vec<PollFd>>();
+    if let Err(e) = poll(&mut poll_fds, -1) {
+        debug!("poll returned error: {}", e);
+    }
+}
+
+fn handle_request(store: &mut HdlStore, request: Request) -> Response {
+    match request {
+        Request::Command { effect, workspace } => {
+            let name = format!("{:?}", effect);
+            match store.dispatch(action::IpcCommand { effect, workspace }) {
+                Ok(()) if store.ipc_command_failed => {
+                    Response::Error(format!("Could not run {}", name))
+                }
+                Ok(()) => Response::Ok,
+                Err(e) => Response::Error(e.to_string()),
             }
-            /*xlib::KeyRelease => {
+        }
+        Request::Query(Query::State) => Response::State(StateSummary::from_state(store)),
+        Request::Query(Query::Dump) => Response::Dump(StateDump::from_state(store)),
+        Request::Subscribe => Response::Error("Subscribe is handled by the ipc server".into()),
+    }
+}
+
+fn handle_xevent(store: &mut HdlStore, xevent: xlib::XEvent) {
+    // debug!("Event: {:?}", xevent);
+    match xevent.get_type() {
+        xlib::ConfigureRequest => {
+            let event = xlib::XConfigureRequestEvent::from(xevent);
+            let window_changes = WindowChanges {
+                x: event.x,
+                y: event.y,
+                width: event.width,
+                height: event.height,
+                border_width: event.border_width,
+                sibling: event.above,
+                stack_mode: event.detail,
+            };
+            store.dispatch(action::ConfigurationRequest {
+                win: event.window,
+                win_changes: window_changes,
+                value_mask: event.value_mask,
+                parent: event.parent,
+            });
+        }
+        xlib::MapRequest => {
+            let event = xlib::XMapRequestEvent::from(xevent);
+            /*debug!(
+            "window type: {}",
+            xlib.get_window_type(event.window).get_name()
+            );*/
+            store.dispatch(action::MapRequest {
+                win: event.window,
+                parent: event.parent,
+            });
+        }
+        xlib::UnmapNotify => {
+            let event = xlib::XUnmapEvent::from(xevent);
+            store.dispatch(action::UnmapNotify { win: event.window });
+        }
+        xlib::ButtonPress => {
+            let event = xlib::XButtonEvent::from(xevent);
+            store.dispatch(action::ButtonPress {
+                win: event.window,
+                sub_win: event.subwindow,
+                button: event.button,
+                x_root: event.x_root as u32,
+                y_root: event.y_root as u32,
+                state: event.state as u32,
+            });
+        }
+        xlib::ButtonRelease => {
+            let event = xlib::XButtonEvent::from(xevent);
+            store.dispatch(action::ButtonRelease {
+                win: event.window,
+                sub_win: event.subwindow,
+                button: event.button,
+                x_root: event.x_root as u32,
+                y_root: event.y_root as u32,
+                state: event.state as u32,
+            });
+        }
+        xlib::KeyPress => {
+            let event = xlib::XKeyEvent::from(xevent);
+            store.dispatch(action::KeyPress {
+                win: event.window,
+                state: event.state,
+                keycode: event.keycode,
+            });
+        }
+        xlib::KeyRelease => {
             let event = xlib::XKeyEvent::from(xevent);
-            action::KeyRelease{win: event.window, state: event.state, keycode: event.keycode};
-            },*/
-            xlib::MotionNotify => {
-                //debug!("motion");
+            store.dispatch(action::KeyRelease {
+                win: event.window,
+                state: event.state,
+                keycode: event.keycode,
+            });
+        }
+        xlib::MotionNotify => {
+            //debug!("motion");
 
-                let event = xlib::XMotionEvent::from(xevent);
-                store.dispatch(action::MotionNotify {
-                    win: event.window,
-                    sub_win: event.subwindow,
-                    x_root: event.x_root,
-                    y_root: event.y_root,
-                    state: event.state,
-                });
-            }
-            xlib::EnterNotify => {
-                let event = xlib::XCrossingEvent::from(xevent);
-                store.dispatch(action::EnterNotify {
-                    win: event.window,
-                    sub_win: event.subwindow,
-                });
-            }
-            xlib::LeaveNotify => {
-                let event = xlib::XCrossingEvent::from(xevent);
-                store.dispatch(action::LeaveNotify { win: event.window });
-            }
-            /*xlib::Expose => {
+            let event = xlib::XMotionEvent::from(xevent);
+            store.dispatch(action::MotionNotify {
+                win: event.window,
+                sub_win: event.subwindow,
+                x_root: event.x_root,
+                y_root: event.y_root,
+                state: event.state,
+            });
+        }
+        xlib::EnterNotify => {
+            let event = xlib::XCrossingEvent::from(xevent);
+            store.dispatch(action::EnterNotify {
+                win: event.window,
+                sub_win: event.subwindow,
+            });
+        }
+        xlib::LeaveNotify => {
+            let event = xlib::XCrossingEvent::from(xevent);
+            store.dispatch(action::LeaveNotify { win: event.window });
+        }
+        xlib::Expose => {
             let event = xlib::XExposeEvent::from(xevent);
-            action::Expose{win: event.window};
-            },*/
-            xlib::DestroyNotify => {
-                let event = xlib::XDestroyWindowEvent::from(xevent);
-                store.dispatch(action::Destroy { win: event.window });
-            }
-            xlib::PropertyNotify => {
-                let event = xlib::XPropertyEvent::from(xevent);
-                store.dispatch(action::PropertyNotify {
-                    win: event.window,
-                    atom: event.atom,
-                });
-            }
-            xlib::ClientMessage => {
-                let event = xlib::XClientMessageEvent::from(xevent);
-                //debug!("ClientMessage: {:#?}", event);
-                store.dispatch(action::ClientMessageRequest {
-                    win: event.window,
-                    message_type: event.message_type,
-                    data: vec![
-                        event.data.get_long(0),
-                        event.data.get_long(1),
-                        event.data.get_long(2),
-                    ],
-                });
-            }
-            _ => {
-                store.dispatch(action::UnknownEvent);
+            // Only redraw once the last rectangle of the exposure arrived
+            if event.count == 0 {
+                store.dispatch(action::Expose { win: event.window });
             }
         }
+        xlib::DestroyNotify => {
+            let event = xlib::XDestroyWindowEvent::from(xevent);
+            store.dispatch(action::Destroy { win: event.window });
+        }
+        xlib::PropertyNotify => {
+            let event = xlib::XPropertyEvent::from(xevent);
+            store.dispatch(action::PropertyNotify {
+                win: event.window,
+                atom: event.atom,
+            });
+        }
+        xlib::ClientMessage => {
+            let event = xlib::XClientMessageEvent::from(xevent);
+            //debug!("ClientMessage: {:#?}", event);
+            store.dispatch(action::ClientMessageRequest {
+                win: event.window,
+                message_type: event.message_type,
+                data: vec![
+                    event.data.get_long(0),
+                    event.data.get_long(1),
+                    event.data.get_long(2),
+                ],
+            });
+        }
+        _ => {
+            store.dispatch(action::UnknownEvent);
+        }
     }
 }
@@ -1,26 +1,34 @@
 use {
-    crate::config::{Key, CONFIG},
-    crate::layout::LayoutTag,
-    crate::models::{windowwrapper::*, WindowState},
+    crate::config::{self, Key, CONFIG},
+    crate::ipc::Event,
+    crate::layout::{tabbed, tiling_area, LayoutTag},
+    crate::models::{rect::Rect, windowwrapper::*, WindowState},
     crate::state::*,
+    crate::wm,
     crate::{
         xlibwrapper::xlibmodels::*,
         xlibwrapper::DisplayServer,
         xlibwrapper::{masks::*, util::*},
     },
     reducer::*,
+    std::collections::HashMap,
     std::rc::Rc,
+    std::sync::mpsc::Sender,
 };
 
 pub struct HdlReactor {
     lib: Box<Rc<dyn DisplayServer>>,
     prev_state: State,
+    events: Sender<Event>,
+    tab_strips: HashMap<MonitorId, Window>,
 }
 
 impl Reactor<State> for HdlReactor {
     type Error = Box<dyn std::error::Error>;
 
     fn react(&mut self, state: &State) -> Result<(), Self::Error> {
+        self.publish_events(state);
+
         // Monitors
         if self.prev_state.current_monitor != state.current_monitor {
             let mon = state.monitors.get(&state.current_monitor).ok_or("oops")?;
@@ -66,6 +74,7 @@ impl Reactor<State> for HdlReactor {
         };
 
         let num_of_clients = state.clients().len();
+        let prev_minimized = self.prev_state.minimized();
         for ww in state.clients().values() {
             let window = ww.window();
             if !self.prev_state.clients().contains_key(&window) {
@@ -74,8 +83,12 @@ impl Reactor<State> for HdlReactor {
                     "state.lib.pointer_pos on map: {:?}",
                     state.lib.pointer_pos(state.lib.get_root())
                 );
-                self.lib.add_to_save_set(window);
-                self.lib.add_to_root_net_client_list(window);
+                // Restored windows are still in the save set and the client list
+                if !prev_minimized.contains_key(&window) {
+                    self.lib.add_to_save_set(window);
+                    self.lib.add_to_root_net_client_list(window);
+                    self.lib.set_wm_state(window, NORMAL_STATE);
+                }
                 if ww.current_state != WindowState::Maximized
                     || ww.current_state != WindowState::Monocle
                 {
@@ -85,14 +98,23 @@ impl Reactor<State> for HdlReactor {
                         .set_border_width(window, CONFIG.border_width as u32);
                 }
                 self.lib.set_border_color(window, CONFIG.background_color);
-                self.lib.move_window(window, ww.get_position());
+                if ww.hidden {
+                    self.lib.move_window(window, state.hide_space);
+                } else {
+                    self.lib.move_window(window, ww.get_position());
+                }
                 self.lib.resize_window(window, ww.get_size());
                 self.subscribe_to_events(window);
                 self.lib.map_window(window);
                 self.lib.sync(false);
-                self.set_focus(ww.window(), ww);
-                if let Some(prev_focus) = self.prev_state.clients().get(&self.prev_state.focus_w) {
-                    self.unset_focus(prev_focus.window(), &prev_focus);
+                // Rules may map a window without giving it focus
+                if window == state.focus_w {
+                    self.set_focus(ww.window(), ww);
+                    if let Some(prev_focus) =
+                        self.prev_state.clients().get(&self.prev_state.focus_w)
+                    {
+                        self.unset_focus(prev_focus.window(), &prev_focus);
+                    }
                 }
                 self.lib.sync(true);
                 continue;
@@ -189,23 +211,339 @@ impl Reactor<State> for HdlReactor {
                     debug!("killing window: {}", window);
                     self.kill_window(
                         window,
-                        state.clients().keys().map(|w| *w).collect::<Vec<Window>>(),
+                        state
+                            .clients()
+                            .keys()
+                            .chain(state.minimized().keys())
+                            .map(|w| *w)
+                            .collect::<Vec<Window>>(),
+                    );
+                }
+            }
+        }
+
+        for (w, ww) in state.minimized() {
+            match prev_minimized.get(&w) {
+                None => self.lib.move_window(w, state.hide_space),
+                Some(prev_ww)
+                    if ww.current_state == WindowState::Destroy
+                        && prev_ww.current_state != WindowState::Destroy =>
+                {
+                    self.kill_window(
+                        w,
+                        state
+                            .clients()
+                            .keys()
+                            .chain(state.minimized().keys())
+                            .copied()
+                            .collect::<Vec<Window>>(),
                     );
                 }
+                Some(_) => {}
             }
         }
+
+        // Nothing has focus when the last window went away
+        if self.prev_state.focus_w != state.focus_w && state.focus_w == self.lib.get_root() {
+            self.lib.set_active_window(None);
+        }
+
+        // Focus moved from the keyboard, the cursor goes along
+        if *state.mouse_follow.borrow()
+            && self.prev_state.current_monitor == state.current_monitor
+            && self.prev_state.focus_w != state.focus_w
+        {
+            if let Some(ww) = state.clients().get(&state.focus_w) {
+                if !ww.hidden {
+                    self.lib.flush();
+                    self.lib.center_cursor(ww.window());
+                }
+            }
+        }
+        state.mouse_follow.replace(false);
+
+        // The keyboard is grabbed by the key press starting the switch
+        if self.prev_state.window_switch.is_some() && state.window_switch.is_none() {
+            self.lib.ungrab_keyboard();
+        }
+        self.record_focus(state);
+        self.update_wm_desktops(state);
+        self.update_stacking(state);
+        self.update_workarea(state);
+        self.update_frame_extents(state);
+
+        if self.prev_state.config_generation != state.config_generation {
+            self.apply_config(state);
+        }
+        self.update_tab_strips(state);
         self.prev_state = state.clone();
         Ok(())
     }
 }
 impl HdlReactor {
-    pub fn new(lib: Box<Rc<dyn DisplayServer>>, state: State) -> Self {
+    pub fn new(lib: Box<Rc<dyn DisplayServer>>, state: State, events: Sender<Event>) -> Self {
+        lib.set_workarea(&workareas(&state));
         Self {
             lib,
             prev_state: state,
+            events,
+            tab_strips: HashMap::new(),
+        }
+    }
+
+    fn publish_events(&self, state: &State) {
+        let mut events = vec![];
+
+        if self.prev_state.focus_w != state.focus_w {
+            events.push(Event::FocusChanged {
+                window: state.focus_w,
+            });
+        }
+
+        if self.prev_state.current_monitor != state.current_monitor {
+            events.push(Event::MonitorChanged {
+                monitor: state.current_monitor,
+            });
+        }
+
+        for (id, mon) in state.monitors.iter() {
+            let prev_mon = match self.prev_state.monitors.get(id) {
+                Some(prev_mon) => prev_mon,
+                None => continue,
+            };
+            if prev_mon.current_ws != mon.current_ws {
+                events.push(Event::WorkspaceChanged {
+                    monitor: *id,
+                    workspace: mon.current_ws,
+                });
+            }
+            if prev_mon.get_current_layout() != mon.get_current_layout() {
+                events.push(Event::LayoutChanged {
+                    monitor: *id,
+                    workspace: mon.current_ws,
+                    layout: mon.get_current_layout().into(),
+                });
+            }
+        }
+
+        let (prev_clients, clients) = (self.prev_state.clients(), state.clients());
+        let (prev_minimized, minimized) = (self.prev_state.minimized(), state.minimized());
+        for window in minimized.keys() {
+            if prev_clients.contains_key(window) {
+                events.push(Event::WindowMinimized { window: *window });
+            }
+        }
+        for (window, ww) in clients.iter() {
+            match prev_clients.get(window) {
+                None if prev_minimized.contains_key(window) => {
+                    events.push(Event::WindowRestored { window: *window })
+                }
+                None => events.push(Event::WindowMapped { window: *window }),
+                Some(prev_ww) if prev_ww.current_state != ww.current_state => {
+                    events.push(Event::WindowStateChanged {
+                        window: *window,
+                        previous: prev_ww.current_state.into(),
+                        current: ww.current_state.into(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        prev_clients
+            .keys()
+            .filter(|window| !clients.contains_key(window) && !minimized.contains_key(window))
+            .for_each(|window| events.push(Event::WindowUnmapped { window: *window }));
+
+        for event in events {
+            // Nobody listening is not an error
+            let _ = self.events.send(event);
         }
     }
 
+    /// Moves the focused window to the front of the focus history,
+    /// windows passed by CycleRecent are left out until the switch is committed
+    fn record_focus(&self, state: &State) {
+        if state.window_switch.is_some() {
+            return;
+        }
+        let committed = self.prev_state.window_switch.is_some();
+        let clients = state.clients();
+        if (self.prev_state.focus_w == state.focus_w && !committed)
+            || !clients.contains_key(&state.focus_w)
+        {
+            return;
+        }
+        let mut history = state.focus_history.borrow_mut();
+        history.retain(|w| *w != state.focus_w && clients.contains_key(w));
+        history.insert(0, state.focus_w);
+    }
+
+    /// Sets `_NET_WM_DESKTOP` on clients that were placed or moved since the last state
+    fn update_wm_desktops(&self, state: &State) {
+        let prev = window_desktops(&self.prev_state);
+        for (w, desktop) in window_desktops(state) {
+            if prev.get(&w) != Some(&desktop) {
+                self.lib.set_wm_desktop(w, Some(desktop));
+            }
+        }
+    }
+
+    /// Raising only happens together with focus changes, mapping and unmapping
+    fn update_stacking(&self, state: &State) {
+        let clients = window_desktops(state);
+        let prev_clients = window_desktops(&self.prev_state);
+        if self.prev_state.focus_w == state.focus_w
+            && clients.len() == prev_clients.len()
+            && clients.keys().all(|w| prev_clients.contains_key(w))
+        {
+            return;
+        }
+        self.lib
+            .update_net_client_list_stacking(&clients.keys().copied().collect::<Vec<Window>>());
+    }
+
+    fn update_workarea(&self, state: &State) {
+        let areas = workareas(state);
+        if areas != workareas(&self.prev_state) {
+            self.lib.set_workarea(&areas);
+        }
+    }
+
+    fn update_frame_extents(&self, state: &State) {
+        let reloaded = self.prev_state.config_generation != state.config_generation;
+        let prev_clients = self.prev_state.clients();
+        for (w, ww) in state.clients() {
+            let extents = wm::frame_extents(ww.current_state);
+            if reloaded
+                || prev_clients
+                    .get(&w)
+                    .map(|ww| wm::frame_extents(ww.current_state))
+                    != Some(extents)
+            {
+                self.lib.set_frame_extents(w, extents);
+            }
+        }
+    }
+
+    /// Re-grabs keys and buttons and redraws borders after the config was reloaded
+    fn apply_config(&self, state: &State) {
+        self.lib
+            .set_desktop_names(&config::desktop_names(&CONFIG.workspaces));
+        let root = self.lib.get_root();
+        self.lib.ungrab_keys(root);
+        self.grab_keys(root);
+
+        for ww in state.clients().values() {
+            let window = ww.window();
+            self.lib.ungrab_keys(window);
+            self.lib.ungrab_all_buttons(window);
+            if window == state.focus_w {
+                self.set_focus(window, ww);
+                continue;
+            }
+            if !(ww.current_state == WindowState::Monocle
+                || ww.current_state == WindowState::Maximized)
+            {
+                self.lib
+                    .set_border_width(window, CONFIG.border_width as u32);
+            }
+            self.unset_focus(window, ww);
+        }
+        self.lib.sync(false);
+    }
+
+    /// Draws the title strip of every monitor showing a tabbed layout
+    /// and removes the strips of the ones that stopped showing one
+    fn update_tab_strips(&mut self, state: &State) {
+        for (id, mon) in state.monitors.iter() {
+            let layout = mon.get_current_layout();
+            let stacked = layout == LayoutTag::Stacked;
+            let windows = match mon.get_current_ws() {
+                Some(ws) if layout == LayoutTag::Tabbed || stacked => ws
+                    .clients
+                    .values()
+                    .filter(|ww| !ww.is_trans)
+                    .map(|ww| ww.window())
+                    .collect::<Vec<Window>>(),
+                _ => vec![],
+            };
+            if windows.is_empty() {
+                if let Some(strip) = self.tab_strips.remove(id) {
+                    self.lib.destroy_window(strip);
+                }
+                continue;
+            }
+            if !self.tab_strip_changed(state, *id) {
+                continue;
+            }
+
+            let (rect, _) = tabbed::areas(&mon.screen, &mon.dock_area, windows.len(), stacked);
+            let strip = match self.tab_strips.get(id) {
+                Some(strip) => {
+                    self.lib.move_window(*strip, rect.get_position());
+                    self.lib.resize_window(*strip, rect.get_size());
+                    *strip
+                }
+                None => {
+                    let strip = self.lib.create_simple_window(
+                        self.lib.get_root(),
+                        rect.get_position(),
+                        rect.get_size(),
+                        0,
+                        CONFIG.background_color,
+                        CONFIG.background_color,
+                    );
+                    self.lib.select_input(strip, ExposureMask);
+                    self.lib.map_window(strip);
+                    self.tab_strips.insert(*id, strip);
+                    strip
+                }
+            };
+
+            // Only the focused tab is visible
+            if windows.contains(&state.focus_w) {
+                self.lib.raise_window(state.focus_w);
+            }
+            self.lib.raise_window(strip);
+            let tabs = tabbed::tab_rects(rect, windows.len(), stacked);
+            for (window, tab) in windows.iter().zip(tabs) {
+                let color = if *window == state.focus_w {
+                    CONFIG.focused_background_color
+                } else {
+                    CONFIG.background_color
+                };
+                self.lib.fill_rect(strip, tab, color);
+                let title = self.lib.get_window_name(*window).unwrap_or_default();
+                let (pos, size) = (tab.get_position(), tab.get_size());
+                self.lib.draw_text(
+                    strip,
+                    Position::new(pos.x + 4, pos.y + size.height / 2 + 4),
+                    &title,
+                    CONFIG.tab_text_color,
+                );
+            }
+            self.lib.flush();
+        }
+    }
+
+    fn tab_strip_changed(&self, state: &State, id: MonitorId) -> bool {
+        let (prev_mon, mon) = match (self.prev_state.monitors.get(&id), state.monitors.get(&id)) {
+            (Some(prev_mon), Some(mon)) => (prev_mon, mon),
+            _ => return true,
+        };
+        let clients = |mon: &crate::models::monitor::Monitor| {
+            mon.get_current_ws()
+                .map(|ws| ws.clients.keys().copied().collect::<Vec<Window>>())
+        };
+        !self.tab_strips.contains_key(&id)
+            || self.prev_state.focus_w != state.focus_w
+            || self.prev_state.title_generation != state.title_generation
+            || self.prev_state.config_generation != state.config_generation
+            || prev_mon.current_ws != mon.current_ws
+            || prev_mon.get_current_layout() != mon.get_current_layout()
+            || clients(prev_mon) != clients(mon)
+    }
+
     fn subscribe_to_events(&self, w: Window) {
         self.lib.select_input(
             w,
@@ -252,9 +590,9 @@ impl HdlReactor {
             })
             .filter(|key| !key.is_empty())
             .chain(
-                vec![1, 2, 3, 4, 5, 6, 7, 8, 9]
-                    .iter()
-                    .map(|x| x.to_string()),
+                config::number_keys(&CONFIG.workspaces)
+                    .into_iter()
+                    .map(|(key, _)| key),
             )
             .collect::<Vec<String>>();
 
@@ -276,6 +614,7 @@ impl HdlReactor {
         self.grab_keys(focus);
         self.lib.sync(false);
         self.lib.take_focus(focus);
+        self.lib.set_active_window(Some(focus));
         self.lib.sync(false);
 
         if !(ww.current_state == WindowState::Monocle || ww.current_state == WindowState::Maximized)
@@ -306,3 +645,37 @@ impl HdlReactor {
         // info!("Top level windows: {}", self.lib.top_level_window_count());
     }
 }
+
+/// The desktop of every client, minimized ones included, sticky ones are on all of them
+fn window_desktops(state: &State) -> HashMap<Window, u32> {
+    state
+        .monitors
+        .values()
+        .flat_map(|mon| mon.workspaces.values())
+        .flat_map(|ws| {
+            ws.clients
+                .values()
+                .chain(ws.minimized.iter())
+                .map(move |ww| {
+                    let desktop = if ww.sticky { ALL_DESKTOPS } else { ws.tag };
+                    (ww.window(), desktop)
+                })
+        })
+        .collect()
+}
+
+/// The area left by docks on the monitor of every desktop,
+/// desktops that aren't on any monitor yet would be created on the current one
+fn workareas(state: &State) -> Vec<Rect> {
+    let current = state.monitors.get(&state.current_monitor);
+    (0..config::desktop_names(&CONFIG.workspaces).len() as u32)
+        .filter_map(|tag| {
+            let mon = state
+                .monitors
+                .values()
+                .find(|mon| mon.contains_ws(tag))
+                .or(current)?;
+            Some(tiling_area(&mon.screen, &mon.dock_area, 0))
+        })
+        .collect()
+}
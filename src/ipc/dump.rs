@@ -0,0 +1,288 @@
+use crate::{
+    layout::LayoutTag,
+    models::{monitor::Monitor, rect::Rect, windowwrapper::WindowWrapper, Direction, WindowState},
+    state::State,
+    xlibwrapper::DisplayServer,
+};
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever a field is renamed or removed, adding fields does not break readers.
+pub const DUMP_VERSION: u32 = 1;
+
+/// Snapshot of the whole window tree.
+/// Kept apart from the models themselves so they can change without breaking tooling.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateDump {
+    pub version: u32,
+    pub focus: u64,
+    pub current_monitor: u32,
+    pub monitors: Vec<MonitorDump>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MonitorDump {
+    pub id: u32,
+    pub screen: RectDump,
+    pub dock_area: Option<RectDump>,
+    pub current_workspace: u32,
+    pub workspaces: Vec<WorkspaceDump>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceDump {
+    pub tag: u32,
+    pub layout: LayoutDump,
+    pub focus: u64,
+    pub clients: Vec<ClientDump>,
+    /// Oldest first
+    pub minimized: Vec<ClientDump>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClientDump {
+    pub window: u64,
+    pub class: Option<String>,
+    pub instance: Option<String>,
+    pub rect: RectDump,
+    pub state: WindowStateDump,
+    pub previous_state: WindowStateDump,
+    pub hidden: bool,
+    pub is_trans: bool,
+    pub sticky: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RectDump {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl From<Rect> for RectDump {
+    fn from(rect: Rect) -> Self {
+        let (pos, size) = (rect.get_position(), rect.get_size());
+        Self {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// Mirrors `WindowState`, converted explicitly so the model can change without the dump
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowStateDump {
+    Snapped(DirectionDump),
+    Maximized,
+    Monocle,
+    Free,
+    Tiled,
+    Destroy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectionDump {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// Mirrors `LayoutTag`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum LayoutDump {
+    Floating,
+    ColumnMaster,
+    RightMaster,
+    TopMaster,
+    BottomMaster,
+    CenteredMaster,
+    Dwindle,
+    Spiral,
+    Bsp,
+    Grid,
+    Tabbed,
+    Stacked,
+    Scrolling,
+    Scripted(String),
+}
+
+impl From<WindowState> for WindowStateDump {
+    fn from(state: WindowState) -> Self {
+        match state {
+            WindowState::Snapped(direction) => Self::Snapped(direction.into()),
+            WindowState::Maximized => Self::Maximized,
+            WindowState::Monocle => Self::Monocle,
+            WindowState::Free => Self::Free,
+            WindowState::Tiled => Self::Tiled,
+            WindowState::Destroy => Self::Destroy,
+        }
+    }
+}
+
+impl From<Direction> for DirectionDump {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Self::North,
+            Direction::NorthEast => Self::NorthEast,
+            Direction::East => Self::East,
+            Direction::SouthEast => Self::SouthEast,
+            Direction::South => Self::South,
+            Direction::SouthWest => Self::SouthWest,
+            Direction::West => Self::West,
+            Direction::NorthWest => Self::NorthWest,
+        }
+    }
+}
+
+impl From<LayoutTag> for LayoutDump {
+    fn from(tag: LayoutTag) -> Self {
+        match tag {
+            LayoutTag::Floating => Self::Floating,
+            LayoutTag::ColumnMaster => Self::ColumnMaster,
+            LayoutTag::RightMaster => Self::RightMaster,
+            LayoutTag::TopMaster => Self::TopMaster,
+            LayoutTag::BottomMaster => Self::BottomMaster,
+            LayoutTag::CenteredMaster => Self::CenteredMaster,
+            LayoutTag::Dwindle => Self::Dwindle,
+            LayoutTag::Spiral => Self::Spiral,
+            LayoutTag::Bsp => Self::Bsp,
+            LayoutTag::Grid => Self::Grid,
+            LayoutTag::Tabbed => Self::Tabbed,
+            LayoutTag::Stacked => Self::Stacked,
+            LayoutTag::Scrolling => Self::Scrolling,
+            LayoutTag::Scripted(name) => Self::Scripted(name),
+        }
+    }
+}
+
+impl StateDump {
+    pub(crate) fn from_state(state: &State) -> Self {
+        let mut monitors = state
+            .monitors
+            .values()
+            .map(|mon| MonitorDump::new(&**state.lib, mon))
+            .collect::<Vec<MonitorDump>>();
+        monitors.sort_by_key(|mon| mon.id);
+        Self {
+            version: DUMP_VERSION,
+            focus: state.focus_w,
+            current_monitor: state.current_monitor,
+            monitors,
+        }
+    }
+}
+
+impl MonitorDump {
+    fn new(lib: &dyn DisplayServer, mon: &Monitor) -> Self {
+        let screen = &mon.screen;
+        let mut workspaces = mon
+            .workspaces
+            .values()
+            .map(|ws| WorkspaceDump {
+                tag: ws.tag,
+                layout: ws.get_current_layout().into(),
+                focus: ws.focus_w,
+                clients: ws
+                    .clients
+                    .values()
+                    .map(|ww| ClientDump::new(lib, ww))
+                    .collect(),
+                minimized: ws
+                    .minimized
+                    .iter()
+                    .map(|ww| ClientDump::new(lib, ww))
+                    .collect(),
+            })
+            .collect::<Vec<WorkspaceDump>>();
+        workspaces.sort_by_key(|ws| ws.tag);
+        Self {
+            id: mon.id,
+            screen: RectDump {
+                x: screen.x,
+                y: screen.y,
+                width: screen.width,
+                height: screen.height,
+            },
+            dock_area: mon.dock_area.as_rect(screen).map(RectDump::from),
+            current_workspace: mon.current_ws,
+            workspaces,
+        }
+    }
+}
+
+impl ClientDump {
+    fn new(lib: &dyn DisplayServer, ww: &WindowWrapper) -> Self {
+        let (class, instance) = match lib.get_class_hint(ww.window()) {
+            Ok((class, instance)) => (Some(class), Some(instance)),
+            Err(_) => (None, None),
+        };
+        Self {
+            window: ww.window(),
+            class,
+            instance,
+            rect: ww.window_rect.into(),
+            state: ww.current_state.into(),
+            previous_state: ww.previous_state.into(),
+            hidden: ww.hidden,
+            is_trans: ww.is_trans,
+            sticky: ww.sticky,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RectDump, StateDump, WindowStateDump, DUMP_VERSION};
+    use crate::{
+        models::{rect::Rect, Direction, WindowState},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    #[test]
+    fn rect_from_rect() {
+        let rect = Rect::new(
+            Position { x: 10, y: 20 },
+            Size {
+                width: 300,
+                height: 400,
+            },
+        );
+        assert_eq!(
+            RectDump {
+                x: 10,
+                y: 20,
+                width: 300,
+                height: 400
+            },
+            RectDump::from(rect)
+        )
+    }
+
+    #[test]
+    fn version_is_serialized() {
+        let dump = StateDump {
+            version: DUMP_VERSION,
+            focus: 0,
+            current_monitor: 0,
+            monitors: vec![],
+        };
+        let line = serde_json::to_string(&dump).unwrap();
+        assert!(line.starts_with(&format!(r#"{{"version":{}"#, DUMP_VERSION)))
+    }
+
+    #[test]
+    fn window_state_keeps_its_name() {
+        let state = WindowStateDump::from(WindowState::Snapped(Direction::West));
+        assert_eq!(
+            r#"{"Snapped":"West"}"#,
+            serde_json::to_string(&state).unwrap()
+        )
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{config::KeyEffect, state::State};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    io::{self, ErrorKind, Read, Write},
+    os::unix::{
+        io::{AsRawFd, RawFd},
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+};
+
+mod dump;
+
+pub use dump::*;
+
+pub type ClientId = u64;
+
+/// A single line sent to the control socket, encoded as json.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Command {
+        effect: KeyEffect,
+        /// Numbered from 1 like the `workspaces` of the config, 0 is rejected
+        workspace: Option<u32>,
+    },
+    Query(Query),
+    Subscribe,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    State,
+    Dump,
+}
+
+/// The line written back to the client for every request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Ok,
+    Error(String),
+    State(StateSummary),
+    Dump(StateDump),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateSummary {
+    pub focus: u64,
+    pub current_monitor: u32,
+    pub monitors: Vec<MonitorSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MonitorSummary {
+    pub id: u32,
+    pub current_workspace: u32,
+    pub layout: LayoutDump,
+    pub clients: usize,
+}
+
+/// Pushed to every subscribed client, one json line per change.
+/// Layouts and window states go through the dump enums so model changes don't reach subscribers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    FocusChanged {
+        window: u64,
+    },
+    MonitorChanged {
+        monitor: u32,
+    },
+    WorkspaceChanged {
+        monitor: u32,
+        workspace: u32,
+    },
+    LayoutChanged {
+        monitor: u32,
+        workspace: u32,
+        layout: LayoutDump,
+    },
+    WindowMapped {
+        window: u64,
+    },
+    WindowUnmapped {
+        window: u64,
+    },
+    /// Still managed, `RestoreMinimized` or `_NET_ACTIVE_WINDOW` brings it back
+    WindowMinimized {
+        window: u64,
+    },
+    WindowRestored {
+        window: u64,
+    },
+    WindowStateChanged {
+        window: u64,
+        previous: WindowStateDump,
+        current: WindowStateDump,
+    },
+}
+
+impl StateSummary {
+    pub(crate) fn from_state(state: &State) -> Self {
+        let mut monitors = state
+            .monitors
+            .values()
+            .map(|mon| MonitorSummary {
+                id: mon.id,
+                current_workspace: mon.current_ws,
+                layout: mon.get_current_layout().into(),
+                clients: mon.get_client_keys().len(),
+            })
+            .collect::<Vec<MonitorSummary>>();
+        monitors.sort_by_key(|mon| mon.id);
+        Self {
+            focus: state.focus_w,
+            current_monitor: state.current_monitor,
+            monitors,
+        }
+    }
+}
+
+/// Location of the control socket.
+/// Can be overridden with `HADLOCK_SOCKET`, otherwise it lives in
+/// `$XDG_RUNTIME_DIR` and is suffixed with the display so that nested
+/// sessions don't collide.
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = env::var_os("HADLOCK_SOCKET") {
+        return PathBuf::from(path);
+    }
+    let dir = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir);
+    let display = env::var("DISPLAY").unwrap_or_default();
+    dir.join(format!("hadlock{}.sock", display))
+}
+
+/// A subscriber that stops reading is dropped once this much output is waiting for it
+const MAX_PENDING: usize = 1 << 20;
+
+struct Client {
+    id: ClientId,
+    stream: UnixStream,
+    buffer: Vec<u8>,
+    /// Written out as the socket becomes writable, lines are never split between clients
+    pending: Vec<u8>,
+    subscribed: bool,
+}
+
+pub struct IpcServer {
+    listener: UnixListener,
+    path: PathBuf,
+    clients: Vec<Client>,
+    next_id: ClientId,
+}
+
+impl IpcServer {
+    pub fn bind() -> io::Result<Self> {
+        let path = socket_path();
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        info!("Listening for commands on: {:?}", path);
+        Ok(Self {
+            listener,
+            path,
+            clients: vec![],
+            next_id: 0,
+        })
+    }
+
+    /// Every fd that should wake the event loop up
+    pub fn fds(&self) -> Vec<RawFd> {
+        std::iter::once(self.listener.as_raw_fd())
+            .chain(self.clients.iter().map(|c| c.stream.as_raw_fd()))
+            .collect()
+    }
+
+    /// Fds of clients with output left to write, the event loop waits for them to become writable
+    pub fn pending_fds(&self) -> Vec<RawFd> {
+        self.clients
+            .iter()
+            .filter(|c| !c.pending.is_empty())
+            .map(|c| c.stream.as_raw_fd())
+            .collect()
+    }
+
+    /// Writes as much of the queued output as the sockets take without blocking
+    pub fn flush(&mut self) {
+        let failed = self
+            .clients
+            .iter_mut()
+            .filter(|c| !c.pending.is_empty())
+            .filter_map(|c| match write_pending(c) {
+                Ok(()) => None,
+                Err(e) => {
+                    debug!("ipc client {} write error: {}", c.id, e);
+                    Some(c.id)
+                }
+            })
+            .collect::<Vec<ClientId>>();
+        self.clients.retain(|c| !failed.contains(&c.id));
+    }
+
+    /// Accepts pending connections and returns every complete line received since last call.
+    /// Lines that could not be parsed are returned as errors so the caller can answer them.
+    pub fn poll_requests(&mut self) -> Vec<(ClientId, Result<Request, String>)> {
+        self.accept();
+
+        let mut ret = vec![];
+        let mut closed = vec![];
+        for client in self.clients.iter_mut() {
+            let mut chunk = [0u8; 1024];
+            loop {
+                match client.stream.read(&mut chunk) {
+                    Ok(0) => {
+                        closed.push(client.id);
+                        break;
+                    }
+                    Ok(n) => client.buffer.extend_from_slice(&chunk[..n]),
+                    Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        debug!("ipc client {} read error: {}", client.id, e);
+                        closed.push(client.id);
+                        break;
+                    }
+                }
+            }
+
+            while let Some(index) = client.buffer.iter().position(|b| *b == b'\n') {
+                let line = client.buffer.drain(..=index).collect::<Vec<u8>>();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let request = serde_json::from_str::<Request>(line).map_err(|e| e.to_string());
+                ret.push((client.id, request));
+            }
+        }
+
+        // Requests from clients that hung up are still served, the reply is simply dropped
+        self.clients.retain(|c| !closed.contains(&c.id));
+        ret
+    }
+
+    pub fn reply(&mut self, id: ClientId, response: &Response) {
+        let line = match serde_json::to_string(response) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize ipc response: {}", e);
+                return;
+            }
+        };
+        self.send_line(id, &line);
+    }
+
+    pub fn subscribe(&mut self, id: ClientId) {
+        if let Some(client) = self.clients.iter_mut().find(|c| c.id == id) {
+            client.subscribed = true;
+        }
+    }
+
+    pub fn broadcast(&mut self, event: &Event) {
+        let line = match serde_json::to_string(event) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize ipc event: {}", e);
+                return;
+            }
+        };
+        let subscribers = self
+            .clients
+            .iter()
+            .filter(|c| c.subscribed)
+            .map(|c| c.id)
+            .collect::<Vec<ClientId>>();
+        for id in subscribers {
+            self.send_line(id, &line);
+        }
+    }
+
+    fn send_line(&mut self, id: ClientId, line: &str) {
+        let failed = match self.clients.iter_mut().find(|c| c.id == id) {
+            Some(client) if client.pending.len() + line.len() >= MAX_PENDING => true,
+            Some(client) => {
+                client.pending.extend_from_slice(line.as_bytes());
+                client.pending.push(b'\n');
+                write_pending(client).is_err()
+            }
+            None => false,
+        };
+        if failed {
+            debug!("Dropping ipc client: {}", id);
+            self.clients.retain(|c| c.id != id);
+        }
+    }
+
+    fn accept(&mut self) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        warn!("Failed to set ipc client non blocking: {}", e);
+                        continue;
+                    }
+                    self.clients.push(Client {
+                        id: self.next_id,
+                        stream,
+                        buffer: vec![],
+                        pending: vec![],
+                        subscribed: false,
+                    });
+                    self.next_id += 1;
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    warn!("Failed to accept ipc client: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Writes until the socket would block, whatever is left stays queued
+fn write_pending(client: &mut Client) -> io::Result<()> {
+    while !client.pending.is_empty() {
+        match client.stream.write(&client.pending) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(n) => {
+                client.pending.drain(..n);
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Event, Request, Response, WindowStateDump};
+    use crate::config::{Axis, KeyEffect};
+
+    #[test]
+    fn request_roundtrip() {
+        let request = Request::Command {
+            effect: KeyEffect::Resize(10, Axis::Horizontal),
+            workspace: None,
+        };
+        let line = serde_json::to_string(&request).unwrap();
+        assert_eq!(request, serde_json::from_str::<Request>(&line).unwrap())
+    }
+
+    #[test]
+    fn unit_effect_from_json() {
+        let tested = serde_json::from_str::<Request>(
+            r#"{"Command":{"effect":"ChangeCurrentWorkspace","workspace":3}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Request::Command {
+                effect: KeyEffect::ChangeCurrentWorkspace,
+                workspace: Some(3)
+            },
+            tested
+        )
+    }
+
+    #[test]
+    fn response_is_single_line() {
+        let line = serde_json::to_string(&Response::Error("bad\nrequest".into())).unwrap();
+        assert!(!line.contains('\n'))
+    }
+
+    #[test]
+    fn event_to_json() {
+        let event = Event::WindowStateChanged {
+            window: 4,
+            previous: WindowStateDump::Free,
+            current: WindowStateDump::Monocle,
+        };
+        assert_eq!(
+            r#"{"WindowStateChanged":{"window":4,"previous":"Free","current":"Monocle"}}"#,
+            serde_json::to_string(&event).unwrap()
+        )
+    }
+}
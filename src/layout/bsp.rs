@@ -0,0 +1,606 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{
+        dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
+    },
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+use std::cell::{Cell, RefCell};
+
+const MIN_RATIO: f32 = 0.05;
+const MAX_RATIO: f32 = 0.95;
+
+/// Binary space partition, every window is a leaf in a tree of splits.
+/// The tree is kept between reorders, so splits stay where they were put.
+#[derive(Debug, Clone)]
+pub struct Bsp {
+    pub layout_type: LayoutTag,
+    tree: RefCell<Option<Node>>,
+    presel: Option<Preselection>,
+    focus: Window,
+    // Resize only gets the windows, so the area the tree was last laid out in is kept
+    area: Cell<Option<Rect>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf(Window),
+    Split {
+        axis: Axis,
+        /// Share of the space given to `first`
+        ratio: f32,
+        first: Box<Node>,
+        second: Box<Node>,
+    },
+}
+
+/// Where the next window goes: next to `window`, on the `direction` side,
+/// taking `ratio` of its space
+#[derive(Debug, Clone, PartialEq)]
+struct Preselection {
+    window: Window,
+    direction: Direction,
+    ratio: f32,
+}
+
+impl Node {
+    fn leaves(&self, ret: &mut Vec<Window>) {
+        match self {
+            Node::Leaf(w) => ret.push(*w),
+            Node::Split { first, second, .. } => {
+                first.leaves(ret);
+                second.leaves(ret);
+            }
+        }
+    }
+
+    fn contains(&self, w: Window) -> bool {
+        match self {
+            Node::Leaf(leaf) => *leaf == w,
+            Node::Split { first, second, .. } => first.contains(w) || second.contains(w),
+        }
+    }
+
+    fn split_leaf(
+        &mut self,
+        target: Window,
+        w: Window,
+        axis: &Axis,
+        ratio: f32,
+        new_first: bool,
+    ) -> bool {
+        match self {
+            Node::Leaf(leaf) if *leaf == target => {
+                let (first, second) = if new_first { (w, target) } else { (target, w) };
+                *self = Node::Split {
+                    axis: axis.clone(),
+                    ratio,
+                    first: Box::new(Node::Leaf(first)),
+                    second: Box::new(Node::Leaf(second)),
+                };
+                true
+            }
+            Node::Leaf(_) => false,
+            Node::Split { first, second, .. } => {
+                first.split_leaf(target, w, axis, ratio, new_first)
+                    || second.split_leaf(target, w, axis, ratio, new_first)
+            }
+        }
+    }
+
+    /// The sibling of a removed leaf takes the place of their split
+    fn remove(self, w: Window) -> Option<Node> {
+        match self {
+            Node::Leaf(leaf) if leaf == w => None,
+            leaf @ Node::Leaf(_) => Some(leaf),
+            Node::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => match (first.remove(w), second.remove(w)) {
+                (Some(first), Some(second)) => Some(Node::Split {
+                    axis,
+                    ratio,
+                    first: Box::new(first),
+                    second: Box::new(second),
+                }),
+                (first, second) => first.or(second),
+            },
+        }
+    }
+
+    fn swap(&mut self, a: Window, b: Window) {
+        match self {
+            Node::Leaf(leaf) if *leaf == a => *leaf = b,
+            Node::Leaf(leaf) if *leaf == b => *leaf = a,
+            Node::Leaf(_) => (),
+            Node::Split { first, second, .. } => {
+                first.swap(a, b);
+                second.swap(a, b);
+            }
+        }
+    }
+
+    fn has_child(&self, w: Window) -> bool {
+        match self {
+            Node::Leaf(_) => false,
+            Node::Split { first, second, .. } => {
+                **first == Node::Leaf(w) || **second == Node::Leaf(w)
+            }
+        }
+    }
+
+    fn parent_mut(&mut self, w: Window) -> Option<&mut Node> {
+        if self.has_child(w) {
+            return Some(self);
+        }
+        match self {
+            Node::Leaf(_) => None,
+            Node::Split { first, second, .. } => match first.parent_mut(w) {
+                Some(parent) => Some(parent),
+                None => second.parent_mut(w),
+            },
+        }
+    }
+
+    /// Turns the subtree a quarter clockwise
+    fn rotate(&mut self) {
+        if let Node::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } = self
+        {
+            if *axis == Axis::Vertical {
+                std::mem::swap(first, second);
+                *ratio = 1.0 - *ratio;
+            }
+            *axis = match axis {
+                Axis::Horizontal => Axis::Vertical,
+                Axis::Vertical => Axis::Horizontal,
+            };
+            first.rotate();
+            second.rotate();
+        }
+    }
+
+    /// Mirrors the subtree, `Horizontal` swaps left and right, `Vertical` top and bottom
+    fn flip(&mut self, flip_axis: &Axis) {
+        if let Node::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } = self
+        {
+            if axis == flip_axis {
+                std::mem::swap(first, second);
+                *ratio = 1.0 - *ratio;
+            }
+            first.flip(flip_axis);
+            second.flip(flip_axis);
+        }
+    }
+
+    /// Moves the closest split along `axis` above `w` so `w` grows by `delta`.
+    /// Returns None if `w` isn't in the subtree and whether a split was moved otherwise.
+    fn resize(&mut self, w: Window, resize_axis: &Axis, delta: i32, area: Rect) -> Option<bool> {
+        match self {
+            Node::Leaf(leaf) if *leaf == w => Some(false),
+            Node::Leaf(_) => None,
+            Node::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => {
+                let (first_area, second_area) = split_area(area, axis, *ratio);
+                let (resized, in_first) = match first.resize(w, resize_axis, delta, first_area) {
+                    Some(resized) => (resized, true),
+                    None => (second.resize(w, resize_axis, delta, second_area)?, false),
+                };
+                if resized || axis != resize_axis {
+                    return Some(resized);
+                }
+                let length = match axis {
+                    Axis::Horizontal => area.get_size().width,
+                    Axis::Vertical => area.get_size().height,
+                };
+                let change = delta as f32 / length.max(1) as f32;
+                let change = if in_first { change } else { -change };
+                *ratio = (*ratio + change).clamp(MIN_RATIO, MAX_RATIO);
+                Some(true)
+            }
+        }
+    }
+
+    fn layout(&self, area: Rect, ret: &mut Vec<(Window, Rect)>) {
+        match self {
+            Node::Leaf(w) => ret.push((*w, inside_border(area))),
+            Node::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => {
+                let (first_area, second_area) = split_area(area, axis, *ratio);
+                first.layout(first_area, ret);
+                second.layout(second_area, ret);
+            }
+        }
+    }
+}
+
+fn split_area(area: Rect, axis: &Axis, ratio: f32) -> (Rect, Rect) {
+    let length = match axis {
+        Axis::Horizontal => area.get_size().width,
+        Axis::Vertical => area.get_size().height,
+    };
+    let first = ((length - CONFIG.inner_gap) as f32 * ratio) as i32;
+    split_rect(area, axis, first, CONFIG.inner_gap)
+}
+
+/// The axis of the split and whether the new window comes first
+fn split_side(direction: Direction) -> Option<(Axis, bool)> {
+    match direction {
+        Direction::North => Some((Axis::Vertical, true)),
+        Direction::South => Some((Axis::Vertical, false)),
+        Direction::West => Some((Axis::Horizontal, true)),
+        Direction::East => Some((Axis::Horizontal, false)),
+        _ => None,
+    }
+}
+
+impl Bsp {
+    fn insert(&mut self, w: Window) {
+        let mut tree = self.tree.borrow_mut();
+        let root = match tree.as_mut() {
+            Some(root) if root.contains(w) => return,
+            Some(root) => root,
+            None => {
+                *tree = Some(Node::Leaf(w));
+                return;
+            }
+        };
+
+        let (target, axis, ratio, new_first) = match self.presel.take() {
+            Some(presel) if root.contains(presel.window) => {
+                let (axis, new_first) =
+                    split_side(presel.direction).unwrap_or((Axis::Horizontal, false));
+                (presel.window, axis, presel.ratio, new_first)
+            }
+            _ => {
+                let target = if root.contains(self.focus) {
+                    self.focus
+                } else {
+                    let mut leaves = vec![];
+                    root.leaves(&mut leaves);
+                    *leaves.last().expect("a tree always has a leaf")
+                };
+                // Without a preselection the longest side of the target is split
+                let mut rects = vec![];
+                if let Some(area) = self.area.get() {
+                    root.layout(area, &mut rects);
+                }
+                let axis = match rects.iter().find(|(win, _)| *win == target) {
+                    Some((_, rect)) if rect.get_size().height > rect.get_size().width => {
+                        Axis::Vertical
+                    }
+                    _ => Axis::Horizontal,
+                };
+                (target, axis, 0.5, false)
+            }
+        };
+        let ratio = if new_first { ratio } else { 1.0 - ratio };
+        root.split_leaf(target, w, &axis, ratio, new_first);
+    }
+
+    /// Drops windows that are gone and adds the ones the tree doesn't know yet
+    fn sync(&mut self, windows: &[Window]) {
+        let tree = self.tree.borrow_mut().take();
+        *self.tree.borrow_mut() = tree.and_then(|root| {
+            let mut leaves = vec![];
+            root.leaves(&mut leaves);
+            leaves
+                .into_iter()
+                .filter(|w| !windows.contains(w))
+                .try_fold(root, |root, w| root.remove(w))
+        });
+        for w in windows {
+            self.insert(*w);
+        }
+    }
+
+    fn arrange(&self, screen: &Screen, dock_area: &DockArea) -> Vec<(Window, Rect)> {
+        let area = tiling_area(screen, dock_area, CONFIG.outer_gap);
+        self.area.set(Some(area));
+        match &*self.tree.borrow() {
+            Some(Node::Leaf(w)) => {
+                let gap = if CONFIG.smart_gaps {
+                    0
+                } else {
+                    CONFIG.outer_gap
+                };
+                vec![(*w, tiling_area(screen, dock_area, gap))]
+            }
+            Some(root) => {
+                let mut ret = vec![];
+                root.layout(area, &mut ret);
+                ret
+            }
+            None => vec![],
+        }
+    }
+}
+
+impl Default for Bsp {
+    fn default() -> Self {
+        Self {
+            layout_type: LayoutTag::Bsp,
+            tree: RefCell::new(None),
+            presel: None,
+            focus: 0,
+            area: Cell::new(None),
+        }
+    }
+}
+
+impl std::fmt::Display for Bsp {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Bsp {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn set_focus(&mut self, w: Window) {
+        self.focus = w;
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .into_iter()
+            .filter(|ww| !ww.is_trans && ww.window() != w)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        self.sync(&windows);
+        self.insert(w);
+        self.arrange(screen, dock_area)
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        column_master::ColumnMaster::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn resize(
+        &self,
+        win: Window,
+        axis: &Axis,
+        delta: i32,
+        windows: &[&WindowWrapper],
+    ) -> Vec<WindowWrapper> {
+        if let Some(ww) = windows.iter().find(|ww| ww.is_trans && ww.window() == win) {
+            let size = ww.get_size();
+            let size = match axis {
+                Axis::Horizontal => Size {
+                    width: size.width + delta,
+                    height: size.height,
+                },
+                Axis::Vertical => Size {
+                    width: size.width,
+                    height: size.height + delta,
+                },
+            };
+            return vec![WindowWrapper {
+                window_rect: Rect::new(ww.get_position(), size),
+                ..(**ww).clone()
+            }];
+        }
+
+        let area = match self.area.get() {
+            Some(area) => area,
+            None => return vec![],
+        };
+        let mut rects = vec![];
+        {
+            let mut tree = self.tree.borrow_mut();
+            let root = match tree.as_mut() {
+                Some(root) => root,
+                None => return vec![],
+            };
+            if root.resize(win, axis, delta, area) != Some(true) {
+                return vec![];
+            }
+            root.layout(area, &mut rects);
+        }
+        rects
+            .into_iter()
+            .filter_map(|(w, rect)| {
+                let ww = windows.iter().find(|ww| ww.window() == w)?;
+                Some(WindowWrapper {
+                    window_rect: rect,
+                    ..(**ww).clone()
+                })
+            })
+            .collect()
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        self.focus = focus;
+        let windows = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        self.sync(&windows);
+        self.arrange(screen, dock_area)
+    }
+
+    fn preselect(&mut self, w: Window, direction: Direction, ratio: f32) {
+        if split_side(direction).is_none() {
+            warn!(
+                "Can only preselect North, East, South or West: {:?}",
+                direction
+            );
+            return;
+        }
+        let presel = Preselection {
+            window: w,
+            direction,
+            ratio: ratio.clamp(MIN_RATIO, MAX_RATIO),
+        };
+        // Preselecting the same side twice cancels it
+        self.presel = if self.presel.as_ref() == Some(&presel) {
+            None
+        } else {
+            Some(presel)
+        };
+    }
+
+    fn rotate(&mut self, w: Window) {
+        if let Some(parent) = self
+            .tree
+            .get_mut()
+            .as_mut()
+            .and_then(|root| root.parent_mut(w))
+        {
+            parent.rotate();
+        }
+    }
+
+    fn flip(&mut self, w: Window, axis: &Axis) {
+        if let Some(parent) = self
+            .tree
+            .get_mut()
+            .as_mut()
+            .and_then(|root| root.parent_mut(w))
+        {
+            parent.flip(axis);
+        }
+    }
+
+    fn swap(&mut self, a: Window, b: Window) {
+        if let Some(root) = self.tree.get_mut().as_mut() {
+            if root.contains(a) && root.contains(b) {
+                root.swap(a, b);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Bsp;
+    use crate::{
+        config::Axis,
+        layout::{inside_border, Layout},
+        models::{dockarea::DockArea, rect::Rect, screen::Screen, Direction},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn screen() -> Screen {
+        Screen::new(0, 1000, 800, 0, 0)
+    }
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        inside_border(Rect::new(Position::new(x, y), Size { width, height }))
+    }
+
+    fn bsp(windows: &[u64]) -> Bsp {
+        let mut bsp = Bsp::default();
+        for w in windows {
+            bsp.set_focus(*w);
+            bsp.insert(*w);
+            bsp.arrange(&screen(), &DockArea::default());
+        }
+        bsp
+    }
+
+    #[test]
+    fn splits_focused_window_along_longest_side() {
+        let mut bsp = bsp(&[1, 2]);
+        bsp.set_focus(1);
+        bsp.insert(3);
+        assert_eq!(
+            vec![
+                (1, rect(0, 0, 500, 400)),
+                (3, rect(0, 400, 500, 400)),
+                (2, rect(500, 0, 500, 800)),
+            ],
+            bsp.arrange(&screen(), &DockArea::default())
+        )
+    }
+
+    #[test]
+    fn preselection_rotation_and_removal() {
+        let mut bsp = bsp(&[1]);
+        bsp.preselect(1, Direction::West, 0.25);
+        bsp.insert(2);
+        assert_eq!(
+            vec![(2, rect(0, 0, 250, 800)), (1, rect(250, 0, 750, 800))],
+            bsp.arrange(&screen(), &DockArea::default())
+        );
+
+        bsp.rotate(1);
+        assert_eq!(
+            vec![(2, rect(0, 0, 1000, 200)), (1, rect(0, 200, 1000, 600))],
+            bsp.arrange(&screen(), &DockArea::default())
+        );
+
+        bsp.flip(1, &Axis::Vertical);
+        bsp.sync(&[1]);
+        assert_eq!(
+            vec![(
+                1,
+                Rect::new(
+                    Position::new(0, 0),
+                    Size {
+                        width: 1000,
+                        height: 800
+                    }
+                )
+            )],
+            bsp.arrange(&screen(), &DockArea::default())
+        );
+    }
+
+    #[test]
+    fn swap_trades_leaves() {
+        let mut bsp = bsp(&[1, 2]);
+        bsp.swap(1, 2);
+        assert_eq!(
+            vec![(2, rect(0, 0, 500, 800)), (1, rect(500, 0, 500, 800))],
+            bsp.arrange(&screen(), &DockArea::default())
+        )
+    }
+}
@@ -12,71 +12,157 @@ use crate::{
 };
 use std::cell::RefCell;
 
+pub const MIN_RATIO: f32 = 0.05;
+pub const MAX_RATIO: f32 = 0.95;
+
+/// Where the master area goes, the stack takes the rest of the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasterPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Stacks on both sides of the master
+    Center,
+}
+
+/// The newest windows are masters, side by side, and the rest are stacked
+/// next to them, oldest first.
 #[derive(Debug, Clone)]
 pub struct ColumnMaster {
     offset: RefCell<i32>,
     pub layout_type: LayoutTag,
+    position: MasterPosition,
+    master_count: usize,
+    master_ratio: f32,
+    // Resize only gets the windows, so the screen they were last laid out on is kept
+    last_screen: RefCell<Option<(Screen, DockArea)>>,
 }
 
 impl ColumnMaster {
-    fn column_maximize(
+    pub fn new(layout_type: LayoutTag, position: MasterPosition) -> Self {
+        Self {
+            layout_type,
+            offset: RefCell::new(0),
+            position,
+            master_count: CONFIG.master_count,
+            master_ratio: CONFIG.master_ratio.clamp(MIN_RATIO, MAX_RATIO),
+            last_screen: RefCell::new(None),
+        }
+    }
+
+    /// The axis the master and stack areas are split along
+    fn split_axis(&self) -> Axis {
+        match self.position {
+            MasterPosition::Top | MasterPosition::Bottom => Axis::Vertical,
+            _ => Axis::Horizontal,
+        }
+    }
+
+    /// Length of the master area out of `length`, leaving at least `min` for the stacks
+    fn master_length(&self, length: i32, min: i32) -> i32 {
+        let border = 2 * CONFIG.border_width + 1;
+        ((length as f32 * self.master_ratio) as i32 + *self.offset.borrow())
+            .max(border)
+            .min(length - min)
+    }
+
+    /// `windows` are ordered newest first
+    fn arrange(
         &self,
-        w: Window,
         screen: &Screen,
         dock_area: &DockArea,
-    ) -> (Size, Position) {
-        let gap = if CONFIG.smart_gaps {
-            0
-        } else {
-            CONFIG.outer_gap
-        };
-        let (mut size, mut pos) = (
-            Size {
-                width: screen.width - gap * 2,
-                height: screen.height - gap * 2,
-            },
-            Position {
-                x: screen.x + gap,
-                y: screen.y + gap,
-            },
-        );
-        match dock_area.as_rect(screen) {
-            Some(dock) => {
-                size.height -= dock.get_size().height;
-                pos.y += dock.get_size().height;
-                (size, pos)
-            }
-            None => (size, pos),
+        windows: &[Window],
+    ) -> Vec<(Window, Rect)> {
+        self.last_screen
+            .replace(Some((screen.clone(), dock_area.clone())));
+        if windows.len() == 1 {
+            let gap = if CONFIG.smart_gaps {
+                0
+            } else {
+                CONFIG.outer_gap
+            };
+            return vec![(windows[0], tiling_area(screen, dock_area, gap))];
         }
-    }
 
-    fn column_height(&self, screen: &Screen, dock: &DockArea, column: &[&WindowWrapper]) -> i32 {
-        let dock_height = match dock.as_rect(screen) {
-            Some(dock_rect) => dock_rect.get_size().height,
-            None => 0,
+        let area = tiling_area(screen, dock_area, CONFIG.outer_gap);
+        let master_count = self.master_count.min(windows.len());
+        let (masters, stack) = windows.split_at(master_count);
+        let stack = stack.iter().rev().copied().collect::<Vec<Window>>();
+
+        let gap = CONFIG.inner_gap;
+        let min = 2 * CONFIG.border_width + 1;
+        let axis = self.split_axis();
+        let stack_axis = match axis {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        };
+        let length = match axis {
+            Axis::Horizontal => area.get_size().width,
+            Axis::Vertical => area.get_size().height,
         };
 
-        let mut ret = ((screen.height - dock_height - 2 * CONFIG.outer_gap)
-            / if column.len() > 1 {
-                column.len() as i32
-            } else {
-                1
-            })
-            - 2 * CONFIG.border_width;
+        // Every part is an area, the axis it is split along and the windows in it
+        let parts = if stack.is_empty() {
+            vec![(area, Axis::Horizontal, masters.to_vec())]
+        } else if masters.is_empty() {
+            vec![(area, stack_axis, stack)]
+        } else {
+            match self.position {
+                MasterPosition::Left | MasterPosition::Top => {
+                    let first = self.master_length(length - gap, min);
+                    let (master_area, stack_area) = split_rect(area, &axis, first, gap);
+                    vec![
+                        (master_area, Axis::Horizontal, masters.to_vec()),
+                        (stack_area, stack_axis, stack),
+                    ]
+                }
+                MasterPosition::Right | MasterPosition::Bottom => {
+                    let first = length - gap - self.master_length(length - gap, min);
+                    let (stack_area, master_area) = split_rect(area, &axis, first, gap);
+                    vec![
+                        (master_area, Axis::Horizontal, masters.to_vec()),
+                        (stack_area, stack_axis, stack),
+                    ]
+                }
+                MasterPosition::Center if stack.len() == 1 => {
+                    let first = self.master_length(length - gap, min);
+                    let (master_area, stack_area) = split_rect(area, &axis, first, gap);
+                    vec![
+                        (master_area, Axis::Horizontal, masters.to_vec()),
+                        (stack_area, stack_axis, stack),
+                    ]
+                }
+                MasterPosition::Center => {
+                    let master = self.master_length(length - 2 * gap, 2 * min);
+                    let side = (length - 2 * gap - master) / 2;
+                    let (left_area, rest) = split_rect(area, &axis, side, gap);
+                    let (master_area, right_area) = split_rect(rest, &axis, master, gap);
+                    let (left, right) = stack.split_at(stack.len() / 2);
+                    vec![
+                        (master_area, Axis::Horizontal, masters.to_vec()),
+                        (left_area, stack_axis.clone(), left.to_vec()),
+                        (right_area, stack_axis, right.to_vec()),
+                    ]
+                }
+            }
+        };
 
-        if column.len() > 2 {
-            ret -= ((column.len() as i32 - 1).abs() * CONFIG.inner_gap) / column.len() as i32
-        }
-        ret
+        parts
+            .into_iter()
+            .flat_map(|(part, part_axis, windows)| {
+                let rects = split_even(part, &part_axis, windows.len(), gap);
+                windows
+                    .into_iter()
+                    .zip(rects.into_iter().map(inside_border))
+            })
+            .collect()
     }
 }
 
 impl Default for ColumnMaster {
     fn default() -> Self {
-        Self {
-            layout_type: LayoutTag::ColumnMaster,
-            offset: RefCell::new(0),
-        }
+        Self::new(LayoutTag::ColumnMaster, MasterPosition::Left)
     }
 }
 
@@ -88,7 +174,7 @@ impl std::fmt::Display for ColumnMaster {
 
 impl Layout for ColumnMaster {
     fn get_type(&self) -> LayoutTag {
-        self.layout_type
+        self.layout_type.clone()
     }
 
     fn place_window(
@@ -98,61 +184,16 @@ impl Layout for ColumnMaster {
         w: Window,
         windows: Vec<&WindowWrapper>,
     ) -> Vec<(Window, Rect)> {
-        //debug!("Incoming window vector in column_master: {:#?}", windows);
-        let windows = windows
-            .into_iter()
-            .filter(|ww| !ww.is_trans)
-            .collect::<Vec<&WindowWrapper>>();
-
-        let dock_height = match dock_area.as_rect(screen) {
-            Some(dock_rect) => dock_rect.get_size().height,
-            None => 0,
-        };
-
-        let mut ret_vec = Vec::<(Window, Rect)>::new();
-
-        let column_width = ((screen.width / 2) - 2 * CONFIG.border_width)
-            - (CONFIG.outer_gap + (CONFIG.inner_gap / 2));
-        let column_x = (screen.x + screen.width / 2) + CONFIG.inner_gap + *self.offset.borrow();
-        let offset = *self.offset.borrow();
-        if windows.is_empty() {
-            let (size, pos) = self.column_maximize(w, &screen, &dock_area);
-            ret_vec.push((w, Rect::new(pos, size)));
-            return ret_vec;
-        } else {
-            let size = Size {
-                width: column_width + offset,
-                height: screen.height
-                    - dock_height
-                    - 2 * CONFIG.border_width
-                    - 2 * CONFIG.outer_gap,
-            };
-            let pos = Position {
-                x: screen.x + CONFIG.outer_gap,
-                y: screen.y + dock_height + CONFIG.outer_gap,
-            };
-            let windows = windows
-                .into_iter()
-                .filter(|win| w != win.window())
-                .collect::<Vec<&WindowWrapper>>();
-            for (index, win) in windows.iter().enumerate() {
-                let pos = Position {
-                    x: column_x,
-                    y: ((screen.y + dock_height)
-                        + self.column_height(&screen, &dock_area, &windows) * index as i32)
-                        + (2 * CONFIG.border_width) * index as i32
-                        + CONFIG.outer_gap
-                        + (CONFIG.inner_gap * index as i32),
-                };
-                let size = Size {
-                    width: column_width + (-offset),
-                    height: self.column_height(&screen, &dock_area, &windows),
-                };
-                ret_vec.push((win.window(), Rect::new(pos, size)))
-            }
-            ret_vec.push((w, Rect::new(pos, size)));
-        }
-        ret_vec
+        let windows = std::iter::once(w)
+            .chain(
+                windows
+                    .into_iter()
+                    .rev()
+                    .filter(|ww| !ww.is_trans && ww.window() != w)
+                    .map(|ww| ww.window()),
+            )
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
     }
 
     fn place_window_relative(
@@ -200,17 +241,8 @@ impl Layout for ColumnMaster {
         delta: i32,
         windows: &[&WindowWrapper],
     ) -> Vec<WindowWrapper> {
-        // The new size always refer to master pane (left solo window)
-        // Only X-axis is to be respected
-        *self.offset.borrow_mut() += delta;
-        use std::collections::HashMap;
-        let trans = windows
-            .into_iter()
-            .filter(|ww| ww.is_trans)
-            .map(|ww| (ww.window(), *ww))
-            .collect::<HashMap<Window, &WindowWrapper>>();
-        if let Some(win) = trans.get(&win) {
-            let size = win.get_size();
+        if let Some(ww) = windows.iter().find(|ww| ww.is_trans && ww.window() == win) {
+            let size = ww.get_size();
             let size = if *axis == Axis::Vertical {
                 Size {
                     width: size.width,
@@ -223,48 +255,36 @@ impl Layout for ColumnMaster {
                 }
             };
             return vec![WindowWrapper {
-                window_rect: Rect::new(win.get_position(), size),
-                ..**win
+                window_rect: Rect::new(ww.get_position(), size),
+                ..**ww
             }];
         }
 
-        if windows.len() <= 1 || *axis == Axis::Vertical {
+        // Only the border between the masters and the stack moves
+        let tiled = windows
+            .iter()
+            .rev()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        if tiled.len() <= 1 || *axis != self.split_axis() {
             return vec![];
         }
-        let mut windows = windows
+        *self.offset.borrow_mut() += delta;
+        let (screen, dock_area) = match self.last_screen.borrow().clone() {
+            Some(last) => last,
+            None => return vec![],
+        };
+        self.arrange(&screen, &dock_area, &tiled)
             .into_iter()
-            .filter(|ww| !ww.is_trans)
-            .map(|ww| *ww)
-            .collect::<Vec<&WindowWrapper>>();
-        let mut ret = vec![];
-        if let Some(master) = windows.pop() {
-            let size = master.get_size();
-            ret.push(WindowWrapper {
-                window_rect: Rect::new(
-                    master.get_position(),
-                    Size {
-                        width: size.width + delta,
-                        height: size.height,
-                    },
-                ),
-                ..*master
-            });
-        }
-        for ww in windows {
-            let pos = ww.get_position();
-            let size = ww.get_size();
-            ret.push(WindowWrapper {
-                window_rect: Rect::new(
-                    Position::new(pos.x + delta, pos.y),
-                    Size {
-                        width: size.width + (-delta),
-                        height: size.height,
-                    },
-                ),
-                ..*ww
-            });
-        }
-        ret
+            .filter_map(|(w, rect)| {
+                let ww = windows.iter().find(|ww| ww.window() == w)?;
+                Some(WindowWrapper {
+                    window_rect: rect,
+                    ..**ww
+                })
+            })
+            .collect()
     }
 
     fn reorder(
@@ -274,17 +294,21 @@ impl Layout for ColumnMaster {
         dock_area: &DockArea,
         windows: Vec<WindowWrapper>,
     ) -> Vec<(Window, Rect)> {
-        let mut windows = windows.iter().collect::<Vec<&WindowWrapper>>();
-
+        let windows = windows
+            .iter()
+            .rev()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
         if windows.is_empty() {
             return vec![];
-        } else {
-            let focus = match windows.pop() {
-                Some(ww) => ww.window(),
-                _ => focus,
-            };
-            self.place_window(&dock_area, &screen, focus, windows)
         }
+        self.arrange(screen, dock_area, &windows)
+    }
+
+    fn set_master(&mut self, count: usize, ratio: f32) {
+        self.master_count = count;
+        self.master_ratio = ratio.clamp(MIN_RATIO, MAX_RATIO);
     }
 
     fn shift_window(
@@ -388,3 +412,58 @@ impl Layout for ColumnMaster {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ColumnMaster, MasterPosition};
+    use crate::{
+        layout::{inside_border, LayoutTag},
+        models::{dockarea::DockArea, rect::Rect, screen::Screen},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        inside_border(Rect::new(Position::new(x, y), Size { width, height }))
+    }
+
+    #[test]
+    fn masters_side_by_side() {
+        let layout = ColumnMaster {
+            master_count: 2,
+            master_ratio: 0.6,
+            ..Default::default()
+        };
+        let tested = layout.arrange(
+            &Screen::new(0, 1000, 800, 0, 0),
+            &DockArea::default(),
+            &[4, 3, 2, 1],
+        );
+        assert_eq!(
+            vec![
+                (4, rect(0, 0, 300, 800)),
+                (3, rect(300, 0, 300, 800)),
+                (1, rect(600, 0, 400, 400)),
+                (2, rect(600, 400, 400, 400)),
+            ],
+            tested
+        )
+    }
+
+    #[test]
+    fn centered_master_between_stacks() {
+        let layout = ColumnMaster::new(LayoutTag::CenteredMaster, MasterPosition::Center);
+        let tested = layout.arrange(
+            &Screen::new(0, 1000, 800, 0, 0),
+            &DockArea::default(),
+            &[3, 2, 1],
+        );
+        assert_eq!(
+            vec![
+                (3, rect(250, 0, 500, 800)),
+                (1, rect(0, 0, 250, 800)),
+                (2, rect(750, 0, 250, 800)),
+            ],
+            tested
+        )
+    }
+}
@@ -0,0 +1,360 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{
+        dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
+    },
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+use std::cell::RefCell;
+
+/// Every window takes half of the space left by the previous one, alternating
+/// between side by side and stacked splits. The newest window gets the first half.
+/// In spiral mode the halves are taken clockwise so the windows wind inwards.
+#[derive(Debug, Clone)]
+pub struct Dwindle {
+    pub layout_type: LayoutTag,
+    spiral: bool,
+    // Pixels added to the first horizontal and the first vertical split
+    offset: RefCell<(i32, i32)>,
+    // Resize only gets the windows, so the screen they were last laid out on is kept
+    last_screen: RefCell<Option<(Screen, DockArea)>>,
+}
+
+impl Dwindle {
+    pub fn spiral() -> Self {
+        Self {
+            layout_type: LayoutTag::Spiral,
+            spiral: true,
+            ..Default::default()
+        }
+    }
+
+    /// `windows` are ordered newest first
+    fn arrange(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: &[Window],
+    ) -> Vec<(Window, Rect)> {
+        self.last_screen
+            .replace(Some((screen.clone(), dock_area.clone())));
+        if windows.len() == 1 {
+            let gap = if CONFIG.smart_gaps {
+                0
+            } else {
+                CONFIG.outer_gap
+            };
+            return vec![(windows[0], tiling_area(screen, dock_area, gap))];
+        }
+
+        let (offset_x, offset_y) = *self.offset.borrow();
+        let mut area = tiling_area(screen, dock_area, CONFIG.outer_gap);
+        let mut ret = vec![];
+        for (index, w) in windows.iter().enumerate() {
+            if index == windows.len() - 1 {
+                ret.push((*w, inside_border(area)));
+                break;
+            }
+            let (axis, length, offset) = if index % 2 == 0 {
+                (Axis::Horizontal, area.get_size().width, offset_x)
+            } else {
+                (Axis::Vertical, area.get_size().height, offset_y)
+            };
+            let offset = if index < 2 { offset } else { 0 };
+            let min = 2 * CONFIG.border_width + 1;
+            let first = ((length - CONFIG.inner_gap) / 2 + offset)
+                .max(min)
+                .min(length - CONFIG.inner_gap - min);
+
+            let (first, rest) = split_rect(area, &axis, first, CONFIG.inner_gap);
+            let (win_rect, rest) = if self.spiral && index % 4 >= 2 {
+                (rest, first)
+            } else {
+                (first, rest)
+            };
+            ret.push((*w, inside_border(win_rect)));
+            area = rest;
+        }
+        ret
+    }
+}
+
+/// Side of window `index` the rest of its split lies on, windows are ordered newest first
+fn rest_side(index: usize, spiral: bool) -> Direction {
+    match (index % 2, spiral && index % 4 >= 2) {
+        (0, false) => Direction::East,
+        (0, true) => Direction::West,
+        (_, false) => Direction::South,
+        (_, true) => Direction::North,
+    }
+}
+
+/// Index of the window sharing a split with `index` in `direction`.
+/// Each window splits with the one before it and with the rest that holds the one after it.
+pub fn neighbour(index: usize, count: usize, direction: Direction, spiral: bool) -> Option<usize> {
+    if index + 1 < count && rest_side(index, spiral) == direction {
+        return Some(index + 1);
+    }
+    let back = match rest_side(index.checked_sub(1)?, spiral) {
+        Direction::East => Direction::West,
+        Direction::West => Direction::East,
+        Direction::South => Direction::North,
+        _ => Direction::South,
+    };
+    (index < count && back == direction).then(|| index - 1)
+}
+
+impl Default for Dwindle {
+    fn default() -> Self {
+        Self {
+            layout_type: LayoutTag::Dwindle,
+            spiral: false,
+            offset: RefCell::new((0, 0)),
+            last_screen: RefCell::new(None),
+        }
+    }
+}
+
+impl std::fmt::Display for Dwindle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Dwindle {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = std::iter::once(w)
+            .chain(
+                windows
+                    .into_iter()
+                    .rev()
+                    .filter(|ww| !ww.is_trans && ww.window() != w)
+                    .map(|ww| ww.window()),
+            )
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        column_master::ColumnMaster::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn resize(
+        &self,
+        win: Window,
+        axis: &Axis,
+        delta: i32,
+        windows: &[&WindowWrapper],
+    ) -> Vec<WindowWrapper> {
+        if let Some(ww) = windows.iter().find(|ww| ww.is_trans && ww.window() == win) {
+            let size = ww.get_size();
+            let size = match axis {
+                Axis::Horizontal => Size {
+                    width: size.width + delta,
+                    height: size.height,
+                },
+                Axis::Vertical => Size {
+                    width: size.width,
+                    height: size.height + delta,
+                },
+            };
+            return vec![WindowWrapper {
+                window_rect: Rect::new(ww.get_position(), size),
+                ..(**ww).clone()
+            }];
+        }
+
+        // Only the first two splits can be moved, the first one side by side and
+        // the second one stacked, everything past a split is shrunk to make room
+        let windows = windows
+            .iter()
+            .rev()
+            .filter(|ww| !ww.is_trans)
+            .collect::<Vec<&&WindowWrapper>>();
+        let split = match axis {
+            Axis::Horizontal => 0,
+            Axis::Vertical => 1,
+        };
+        if windows.len() < split + 2 {
+            return vec![];
+        }
+        let (screen, dock_area) = match self.last_screen.borrow().clone() {
+            Some(last) => last,
+            None => return vec![],
+        };
+
+        // Both splits span the whole tiling area, keep the offset within what arrange allows
+        let area = tiling_area(&screen, &dock_area, CONFIG.outer_gap).get_size();
+        let length = match axis {
+            Axis::Horizontal => area.width,
+            Axis::Vertical => area.height,
+        } - CONFIG.inner_gap;
+        let min = 2 * CONFIG.border_width + 1;
+        {
+            let mut offset = self.offset.borrow_mut();
+            let offset = match axis {
+                Axis::Horizontal => &mut offset.0,
+                Axis::Vertical => &mut offset.1,
+            };
+            *offset = (*offset + delta)
+                .min(length - min - length / 2)
+                .max(min - length / 2);
+        }
+
+        let order = windows
+            .iter()
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        self.arrange(&screen, &dock_area, &order)
+            .into_iter()
+            .filter_map(|(w, rect)| {
+                let ww = windows.iter().find(|ww| ww.window() == w)?;
+                Some(WindowWrapper {
+                    window_rect: rect,
+                    ..(***ww).clone()
+                })
+            })
+            .collect()
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .iter()
+            .rev()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        if windows.is_empty() {
+            return vec![];
+        }
+        self.arrange(screen, dock_area, &windows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{neighbour, Dwindle};
+    use crate::{
+        config::{Axis, CONFIG},
+        layout::{inside_border, Layout},
+        models::{
+            dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
+        },
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn screen() -> Screen {
+        Screen::new(0, 1000, 800, 0, 0)
+    }
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        inside_border(Rect::new(Position::new(x, y), Size { width, height }))
+    }
+
+    #[test]
+    fn dwindle_halves_remaining_space() {
+        // Default config has no gaps
+        assert_eq!(0, CONFIG.inner_gap);
+        let tested = Dwindle::default().arrange(&screen(), &DockArea::default(), &[3, 2, 1]);
+        assert_eq!(
+            vec![
+                (3, rect(0, 0, 500, 800)),
+                (2, rect(500, 0, 500, 400)),
+                (1, rect(500, 400, 500, 400)),
+            ],
+            tested
+        )
+    }
+
+    #[test]
+    fn spiral_winds_inwards() {
+        let tested = Dwindle::spiral().arrange(&screen(), &DockArea::default(), &[5, 4, 3, 2, 1]);
+        assert_eq!(
+            vec![
+                (5, rect(0, 0, 500, 800)),
+                (4, rect(500, 0, 500, 400)),
+                (3, rect(750, 400, 250, 400)),
+                (2, rect(500, 600, 250, 200)),
+                (1, rect(500, 400, 250, 200)),
+            ],
+            tested
+        )
+    }
+
+    #[test]
+    fn neighbours_share_a_split() {
+        // 0 | 1
+        //   | --
+        //   | 2
+        assert_eq!(Some(1), neighbour(0, 3, Direction::East, false));
+        assert_eq!(Some(0), neighbour(1, 3, Direction::West, false));
+        assert_eq!(Some(2), neighbour(1, 3, Direction::South, false));
+        assert_eq!(Some(1), neighbour(2, 3, Direction::North, false));
+        assert_eq!(None, neighbour(2, 3, Direction::East, false));
+        assert_eq!(None, neighbour(0, 3, Direction::West, false));
+        // The third split of a spiral puts the rest on the left
+        assert_eq!(Some(3), neighbour(2, 4, Direction::West, true));
+        assert_eq!(Some(2), neighbour(3, 4, Direction::East, true));
+    }
+
+    #[test]
+    fn resize_stops_at_the_edge() {
+        let layout = Dwindle::default();
+        layout.arrange(&screen(), &DockArea::default(), &[2, 1]);
+        // Oldest first, like the workspace keeps them
+        let windows = [1, 2]
+            .iter()
+            .map(|w| WindowWrapper::new(*w, rect(0, 0, 100, 100), false))
+            .collect::<Vec<WindowWrapper>>();
+        let windows = windows.iter().collect::<Vec<&WindowWrapper>>();
+
+        let widths = |delta| {
+            layout
+                .resize(2, &Axis::Horizontal, delta, &windows)
+                .iter()
+                .map(|ww| ww.get_size().width)
+                .collect::<Vec<i32>>()
+        };
+        // The newest window gets all but the smallest width a window can have
+        let min = 2 * CONFIG.border_width + 1;
+        let widest = widths(5000);
+        assert_eq!(
+            vec![
+                rect(0, 0, 1000 - min, 800).get_size().width,
+                rect(0, 0, min, 800).get_size().width,
+            ],
+            widest
+        );
+        // Shrinking works right away instead of first using up the overshoot
+        assert_ne!(widest, widths(-10));
+    }
+}
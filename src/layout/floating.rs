@@ -31,7 +31,7 @@ impl std::fmt::Display for Floating {
 
 impl Layout for Floating {
     fn get_type(&self) -> LayoutTag {
-        self.layout_type
+        self.layout_type.clone()
     }
 
     fn place_window(
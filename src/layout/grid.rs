@@ -0,0 +1,220 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{
+        dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
+    },
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+
+/// Windows in rows of equal cells, oldest first. A last row that isn't full
+/// is stretched across the whole width.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub layout_type: LayoutTag,
+}
+
+/// Columns and rows for `count` windows, `gridBias` decides which one
+/// gets the extra line when they can't be equal
+pub fn dimensions(count: usize) -> (usize, usize) {
+    if count == 0 {
+        return (0, 0);
+    }
+    let mut short = 1;
+    while short * short < count {
+        short += 1;
+    }
+    let long = count.div_ceil(short);
+    match CONFIG.grid_bias {
+        Axis::Horizontal => (short, long),
+        Axis::Vertical => (long, short),
+    }
+}
+
+/// Index of the cell next to `index` in `direction`, windows are laid out row by row
+pub fn neighbour(index: usize, count: usize, direction: Direction) -> Option<usize> {
+    let (cols, rows) = dimensions(count);
+    if index >= count {
+        return None;
+    }
+    let (row, col) = (index / cols, index % cols);
+    let row_len = |row: usize| (count - row * cols).min(cols);
+    // The cells of a short last row are wider, so go by the middle of the cell
+    let column_in = |from_len: usize, col: usize, to_len: usize| {
+        ((2 * col + 1) * to_len / (2 * from_len)).min(to_len - 1)
+    };
+
+    match direction {
+        Direction::West if col > 0 => Some(index - 1),
+        Direction::East if col + 1 < row_len(row) => Some(index + 1),
+        Direction::North if row > 0 => {
+            Some((row - 1) * cols + column_in(row_len(row), col, row_len(row - 1)))
+        }
+        Direction::South if row + 1 < rows => {
+            Some((row + 1) * cols + column_in(row_len(row), col, row_len(row + 1)))
+        }
+        _ => None,
+    }
+}
+
+impl Grid {
+    fn arrange(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: &[Window],
+    ) -> Vec<(Window, Rect)> {
+        if windows.len() == 1 {
+            let gap = if CONFIG.smart_gaps {
+                0
+            } else {
+                CONFIG.outer_gap
+            };
+            return vec![(windows[0], tiling_area(screen, dock_area, gap))];
+        }
+
+        let area = tiling_area(screen, dock_area, CONFIG.outer_gap);
+        let (pos, size) = (area.get_position(), area.get_size());
+        let (cols, rows) = dimensions(windows.len());
+        let cell_height = (size.height - (rows as i32 - 1) * CONFIG.inner_gap) / rows as i32;
+
+        windows
+            .chunks(cols)
+            .enumerate()
+            .flat_map(|(row, row_windows)| {
+                let count = row_windows.len() as i32;
+                let cell_width = (size.width - (count - 1) * CONFIG.inner_gap) / count;
+                let y = pos.y + row as i32 * (cell_height + CONFIG.inner_gap);
+                row_windows.iter().enumerate().map(move |(col, w)| {
+                    let x = pos.x + col as i32 * (cell_width + CONFIG.inner_gap);
+                    (
+                        *w,
+                        inside_border(Rect::new(
+                            Position::new(x, y),
+                            Size {
+                                width: cell_width,
+                                height: cell_height,
+                            },
+                        )),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            layout_type: LayoutTag::Grid,
+        }
+    }
+}
+
+impl std::fmt::Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Grid {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .into_iter()
+            .filter(|ww| !ww.is_trans && ww.window() != w)
+            .map(|ww| ww.window())
+            .chain(std::iter::once(w))
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        column_master::ColumnMaster::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        if windows.is_empty() {
+            return vec![];
+        }
+        self.arrange(screen, dock_area, &windows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dimensions, neighbour, Grid};
+    use crate::{
+        layout::inside_border,
+        models::{dockarea::DockArea, rect::Rect, screen::Screen, Direction},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        inside_border(Rect::new(Position::new(x, y), Size { width, height }))
+    }
+
+    #[test]
+    fn last_row_is_stretched() {
+        // Default config prefers columns
+        assert_eq!((2, 2), dimensions(3));
+        assert_eq!((3, 2), dimensions(5));
+        let tested = Grid::default().arrange(
+            &Screen::new(0, 1000, 800, 0, 0),
+            &DockArea::default(),
+            &[1, 2, 3],
+        );
+        assert_eq!(
+            vec![
+                (1, rect(0, 0, 500, 400)),
+                (2, rect(500, 0, 500, 400)),
+                (3, rect(0, 400, 1000, 400)),
+            ],
+            tested
+        )
+    }
+
+    #[test]
+    fn neighbours() {
+        // 0 1 2
+        //  3 4
+        assert_eq!(Some(1), neighbour(0, 5, Direction::East));
+        assert_eq!(None, neighbour(2, 5, Direction::East));
+        assert_eq!(Some(4), neighbour(2, 5, Direction::South));
+        assert_eq!(Some(3), neighbour(0, 5, Direction::South));
+        assert_eq!(Some(2), neighbour(4, 5, Direction::North));
+        assert_eq!(None, neighbour(4, 5, Direction::South));
+    }
+}
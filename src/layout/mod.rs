@@ -1,9 +1,15 @@
 #![allow(unused_variables)]
 
+pub mod bsp;
 pub mod column_master;
+pub mod dwindle;
 pub mod floating;
+pub mod grid;
+pub mod scripted;
+pub mod scrolling;
+pub mod tabbed;
 
-use crate::config::Axis;
+use crate::config::{Axis, CONFIG};
 use crate::models::{
     dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
 };
@@ -11,19 +17,43 @@ use crate::xlibwrapper::util::{Position, Size};
 use crate::xlibwrapper::xlibmodels::Window;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub enum LayoutTag {
     Floating,
     ColumnMaster,
+    RightMaster,
+    TopMaster,
+    BottomMaster,
+    CenteredMaster,
+    Dwindle,
+    Spiral,
+    Bsp,
+    Grid,
+    Tabbed,
+    Stacked,
+    Scrolling,
+    /// Named after the script file it was loaded from
+    Scripted(String),
 }
 
 impl std::fmt::Display for LayoutTag {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let tag = match *self {
-            Self::Floating => "Floating",
-            Self::ColumnMaster => "ColumnMaster",
-        };
-        write!(f, "{}", tag)
+        match self {
+            Self::Floating => write!(f, "Floating"),
+            Self::ColumnMaster => write!(f, "ColumnMaster"),
+            Self::RightMaster => write!(f, "RightMaster"),
+            Self::TopMaster => write!(f, "TopMaster"),
+            Self::BottomMaster => write!(f, "BottomMaster"),
+            Self::CenteredMaster => write!(f, "CenteredMaster"),
+            Self::Dwindle => write!(f, "Dwindle"),
+            Self::Spiral => write!(f, "Spiral"),
+            Self::Bsp => write!(f, "Bsp"),
+            Self::Grid => write!(f, "Grid"),
+            Self::Tabbed => write!(f, "Tabbed"),
+            Self::Stacked => write!(f, "Stacked"),
+            Self::Scrolling => write!(f, "Scrolling"),
+            Self::Scripted(name) => write!(f, "{}", name),
+        }
     }
 }
 
@@ -50,12 +80,125 @@ pub fn layout_from_tag(tag: LayoutTag) -> Box<dyn Layout> {
     match tag {
         LayoutTag::Floating => Box::new(floating::Floating::default()),
         LayoutTag::ColumnMaster => Box::new(column_master::ColumnMaster::default()),
+        LayoutTag::RightMaster => Box::new(column_master::ColumnMaster::new(
+            tag,
+            column_master::MasterPosition::Right,
+        )),
+        LayoutTag::TopMaster => Box::new(column_master::ColumnMaster::new(
+            tag,
+            column_master::MasterPosition::Top,
+        )),
+        LayoutTag::BottomMaster => Box::new(column_master::ColumnMaster::new(
+            tag,
+            column_master::MasterPosition::Bottom,
+        )),
+        LayoutTag::CenteredMaster => Box::new(column_master::ColumnMaster::new(
+            tag,
+            column_master::MasterPosition::Center,
+        )),
+        LayoutTag::Dwindle => Box::new(dwindle::Dwindle::default()),
+        LayoutTag::Spiral => Box::new(dwindle::Dwindle::spiral()),
+        LayoutTag::Bsp => Box::new(bsp::Bsp::default()),
+        LayoutTag::Grid => Box::new(grid::Grid::default()),
+        LayoutTag::Tabbed => Box::new(tabbed::Tabbed::default()),
+        LayoutTag::Stacked => Box::new(tabbed::Tabbed::stacked()),
+        LayoutTag::Scrolling => Box::new(scrolling::Scrolling::default()),
+        LayoutTag::Scripted(name) => Box::new(scripted::Scripted::new(&name)),
+    }
+}
+
+/// The part of the screen left for tiling once the dock and `gap` are taken away
+pub fn tiling_area(screen: &Screen, dock_area: &DockArea, gap: i32) -> Rect {
+    let (top, bottom, left, right) = dock_area.reserved();
+    Rect::new(
+        Position::new(screen.x + left + gap, screen.y + top + gap),
+        Size {
+            width: screen.width - left - right - 2 * gap,
+            height: screen.height - top - bottom - 2 * gap,
+        },
+    )
+}
+
+/// Cuts `rect` in two along `axis`, the first part is `first` pixels wide (or high)
+/// and the parts are `gap` pixels apart
+pub fn split_rect(rect: Rect, axis: &Axis, first: i32, gap: i32) -> (Rect, Rect) {
+    let (pos, size) = (rect.get_position(), rect.get_size());
+    match axis {
+        Axis::Horizontal => (
+            Rect::new(
+                pos,
+                Size {
+                    width: first,
+                    height: size.height,
+                },
+            ),
+            Rect::new(
+                Position::new(pos.x + first + gap, pos.y),
+                Size {
+                    width: size.width - first - gap,
+                    height: size.height,
+                },
+            ),
+        ),
+        Axis::Vertical => (
+            Rect::new(
+                pos,
+                Size {
+                    width: size.width,
+                    height: first,
+                },
+            ),
+            Rect::new(
+                Position::new(pos.x, pos.y + first + gap),
+                Size {
+                    width: size.width,
+                    height: size.height - first - gap,
+                },
+            ),
+        ),
+    }
+}
+
+/// Cuts `rect` in `count` equal parts along `axis`, `gap` pixels apart.
+/// The last part takes what is left over from rounding.
+pub fn split_even(rect: Rect, axis: &Axis, count: usize, gap: i32) -> Vec<Rect> {
+    let mut ret = vec![];
+    let mut rest = rect;
+    for index in 0..count {
+        let left = (count - index) as i32;
+        if left == 1 {
+            ret.push(rest);
+            break;
+        }
+        let length = match axis {
+            Axis::Horizontal => rest.get_size().width,
+            Axis::Vertical => rest.get_size().height,
+        };
+        let (part, remainder) = split_rect(rest, axis, (length - (left - 1) * gap) / left, gap);
+        ret.push(part);
+        rest = remainder;
     }
+    ret
+}
+
+/// X sizes exclude the border, so tiled windows give up room for it
+pub fn inside_border(rect: Rect) -> Rect {
+    let size = rect.get_size();
+    Rect::new(
+        rect.get_position(),
+        Size {
+            width: size.width - 2 * CONFIG.border_width,
+            height: size.height - 2 * CONFIG.border_width,
+        },
+    )
 }
 
 pub trait Layout: std::fmt::Debug + std::fmt::Display + LayoutClone {
     fn get_type(&self) -> LayoutTag;
 
+    /// Told which window has focus right before a new window is placed
+    fn set_focus(&mut self, w: Window) {}
+
     fn place_window(
         &mut self,
         dock_area: &DockArea,
@@ -182,4 +325,20 @@ pub trait Layout: std::fmt::Debug + std::fmt::Display + LayoutClone {
     ) -> Vec<WindowWrapper> {
         unimplemented!();
     }
+
+    /// Sets where the next window is placed, `ratio` is the share of `w`'s space it gets
+    fn preselect(&mut self, w: Window, direction: Direction, ratio: f32) {}
+
+    /// Turns the split `w` is part of a quarter clockwise
+    fn rotate(&mut self, w: Window) {}
+
+    /// Mirrors the split `w` is part of
+    fn flip(&mut self, w: Window, axis: &Axis) {}
+
+    /// Trades the places of `a` and `b`, for layouts that don't place windows by age
+    fn swap(&mut self, a: Window, b: Window) {}
+
+    /// How many windows share the master area and the share of the screen it gets,
+    /// kept by the workspace and ignored by layouts without one
+    fn set_master(&mut self, count: usize, ratio: f32) {}
 }
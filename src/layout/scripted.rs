@@ -0,0 +1,346 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{
+        dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper, Direction,
+    },
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+use lazy_static::*;
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+/// Every script has to define this function, it receives the screen, the dock
+/// area (or `()`), the gaps and the window ids to lay out, oldest first.
+/// It returns an array of `#{window, x, y, width, height}` maps.
+const ENTRY_POINT: &str = "layout";
+
+lazy_static! {
+    static ref ENGINE: Engine = {
+        let mut engine = Engine::new();
+        // A broken script must not be able to hang the window manager
+        engine.set_max_operations(1_000_000);
+        engine
+    };
+    static ref SCRIPTS: RwLock<BTreeMap<String, Arc<AST>>> = RwLock::new(load_scripts());
+}
+
+/// Layout scripts live in `layouts/` next to the config file,
+/// or in `$XDG_CONFIG_HOME/hadlock/layouts` if hadlock was started without one.
+pub fn script_dir() -> Option<PathBuf> {
+    if let Some(path) = CONFIG.path() {
+        return path.parent().map(|dir| dir.join("layouts"));
+    }
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("hadlock").join("layouts"))
+}
+
+/// Names of every loaded script, in the order they are cycled through
+pub fn names() -> Vec<String> {
+    match SCRIPTS.read() {
+        Ok(scripts) => scripts.keys().cloned().collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Reads the script directory again, called when the config is reloaded
+pub fn reload() {
+    let scripts = load_scripts();
+    if let Ok(mut current) = SCRIPTS.write() {
+        *current = scripts;
+    }
+}
+
+fn load_scripts() -> BTreeMap<String, Arc<AST>> {
+    let mut scripts = BTreeMap::new();
+    let dir = match script_dir() {
+        Some(dir) if dir.is_dir() => dir,
+        _ => return scripts,
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read layout scripts in {:?}: {}", dir, e);
+            return scripts;
+        }
+    };
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("rhai") {
+            continue;
+        }
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        match ENGINE.compile_file(path.clone()) {
+            Ok(ast) if has_entry_point(&ast) => {
+                info!("Loaded layout script: {}", name);
+                scripts.insert(name, Arc::new(ast));
+            }
+            Ok(_) => warn!(
+                "Layout script {:?} does not define fn {}(screen, dock, gaps, clients)",
+                path, ENTRY_POINT
+            ),
+            Err(e) => warn!("Failed to compile layout script {:?}: {}", path, e),
+        }
+    }
+    scripts
+}
+
+fn has_entry_point(ast: &AST) -> bool {
+    ast.iter_functions()
+        .any(|f| f.name == ENTRY_POINT && f.params.len() == 4)
+}
+
+#[derive(Debug, Clone)]
+pub struct Scripted {
+    pub layout_type: LayoutTag,
+    ast: Option<Arc<AST>>,
+}
+
+impl Scripted {
+    pub fn new(name: &str) -> Self {
+        let ast = SCRIPTS
+            .read()
+            .ok()
+            .and_then(|scripts| scripts.get(name).cloned());
+        if ast.is_none() {
+            warn!("No layout script named: {}", name);
+        }
+        Self {
+            layout_type: LayoutTag::Scripted(name.to_string()),
+            ast,
+        }
+    }
+
+    fn arrange(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        clients: Vec<Window>,
+    ) -> Vec<(Window, Rect)> {
+        let ast = match &self.ast {
+            Some(ast) => ast,
+            None => return vec![],
+        };
+
+        let dock = match dock_area.as_rect(screen) {
+            Some(dock) => Dynamic::from_map(rect_to_map(&dock)),
+            None => Dynamic::UNIT,
+        };
+        let mut gaps = Map::new();
+        gaps.insert("outer".into(), (CONFIG.outer_gap as i64).into());
+        gaps.insert("inner".into(), (CONFIG.inner_gap as i64).into());
+        gaps.insert("border".into(), (CONFIG.border_width as i64).into());
+        gaps.insert("smart".into(), CONFIG.smart_gaps.into());
+        let clients = clients
+            .into_iter()
+            .map(|w| Dynamic::from(w as i64))
+            .collect::<Array>();
+
+        let (pos, size): (Position, Size) = screen.clone().into();
+        let result = ENGINE.call_fn::<Array>(
+            &mut Scope::new(),
+            ast,
+            ENTRY_POINT,
+            (rect_to_map(&Rect::new(pos, size)), dock, gaps, clients),
+        );
+        match result {
+            Ok(rects) => rects.into_iter().filter_map(map_to_placement).collect(),
+            Err(e) => {
+                error!("Layout script {} failed: {}", self.layout_type, e);
+                vec![]
+            }
+        }
+    }
+}
+
+fn rect_to_map(rect: &Rect) -> Map {
+    let (pos, size) = (rect.get_position(), rect.get_size());
+    let mut map = Map::new();
+    map.insert("x".into(), (pos.x as i64).into());
+    map.insert("y".into(), (pos.y as i64).into());
+    map.insert("width".into(), (size.width as i64).into());
+    map.insert("height".into(), (size.height as i64).into());
+    map
+}
+
+fn map_to_placement(value: Dynamic) -> Option<(Window, Rect)> {
+    let map = value.try_cast::<Map>()?;
+    let get = |key: &str| map.get(key).and_then(|v| v.as_int().ok());
+    Some((
+        get("window")? as Window,
+        Rect::new(
+            Position::new(get("x")? as i32, get("y")? as i32),
+            Size {
+                width: get("width")? as i32,
+                height: get("height")? as i32,
+            },
+        ),
+    ))
+}
+
+impl std::fmt::Display for Scripted {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Scripted {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let clients = windows
+            .into_iter()
+            .filter(|ww| !ww.is_trans && ww.window() != w)
+            .map(|ww| ww.window())
+            .chain(std::iter::once(w))
+            .collect::<Vec<Window>>();
+        let mut ret = self.arrange(screen, dock_area, clients);
+
+        // The new window has to be placed somewhere even if the script failed to
+        if !ret.iter().any(|(win, _)| *win == w) {
+            ret.extend(floating::Floating::default().place_window(dock_area, screen, w, vec![]));
+        }
+        ret
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        floating::Floating::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let clients = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, clients)
+    }
+
+    fn shift_window(
+        &self,
+        screen: &Screen,
+        ww: &WindowWrapper,
+        dock_area: &DockArea,
+        w: Window,
+        direction: Direction,
+    ) -> Vec<WindowWrapper> {
+        floating::Floating::default().shift_window(screen, ww, dock_area, w, direction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{map_to_placement, Scripted, ENGINE};
+    use crate::{
+        models::{dockarea::DockArea, rect::Rect, screen::Screen},
+        xlibwrapper::util::{Position, Size},
+    };
+    use std::sync::Arc;
+
+    const COLUMNS: &str = r#"
+        fn layout(screen, dock, gaps, clients) {
+            let width = screen.width / clients.len();
+            let ret = [];
+            for (w, i) in clients {
+                ret.push(#{ window: w, x: screen.x + i * width, y: screen.y, width: width, height: screen.height });
+            }
+            ret
+        }
+    "#;
+
+    fn screen() -> Screen {
+        Screen {
+            root: 0,
+            x: 0,
+            y: 0,
+            width: 1000,
+            height: 500,
+        }
+    }
+
+    fn layout(script: &str) -> Scripted {
+        Scripted {
+            layout_type: crate::layout::LayoutTag::Scripted("test".into()),
+            ast: Some(Arc::new(ENGINE.compile(script).unwrap())),
+        }
+    }
+
+    #[test]
+    fn script_places_windows() {
+        let tested = layout(COLUMNS).arrange(&screen(), &DockArea::default(), vec![1, 2]);
+        assert_eq!(
+            vec![
+                (
+                    1,
+                    Rect::new(
+                        Position::new(0, 0),
+                        Size {
+                            width: 500,
+                            height: 500
+                        }
+                    )
+                ),
+                (
+                    2,
+                    Rect::new(
+                        Position::new(500, 0),
+                        Size {
+                            width: 500,
+                            height: 500
+                        }
+                    )
+                ),
+            ],
+            tested
+        )
+    }
+
+    #[test]
+    fn failing_script_places_nothing() {
+        let tested = layout("fn layout(screen, dock, gaps, clients) { clients[10] }").arrange(
+            &screen(),
+            &DockArea::default(),
+            vec![1],
+        );
+        assert!(tested.is_empty());
+        assert!(map_to_placement(rhai::Dynamic::from(1_i64)).is_none());
+    }
+}
@@ -0,0 +1,283 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper},
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+/// Windows are full height columns on a strip that can be wider than the screen,
+/// oldest on the left. The strip scrolls to keep the focused column on screen,
+/// columns scrolled out of view are hidden.
+#[derive(Debug, Clone)]
+pub struct Scrolling {
+    pub layout_type: LayoutTag,
+    // Columns that were resized, the others get half of the screen
+    widths: RefCell<HashMap<Window, i32>>,
+    // How far the strip is scrolled to the left
+    scroll: Cell<i32>,
+    focus: Cell<Window>,
+    // Resize only gets the windows, so the screen they were last laid out on is kept
+    last_screen: RefCell<Option<(Screen, DockArea)>>,
+}
+
+impl Scrolling {
+    /// `windows` are ordered oldest first
+    fn arrange(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: &[Window],
+    ) -> Vec<(Window, Rect)> {
+        self.last_screen
+            .replace(Some((screen.clone(), dock_area.clone())));
+        self.widths.borrow_mut().retain(|w, _| windows.contains(w));
+        if windows.len() == 1 {
+            self.scroll.set(0);
+            let gap = if CONFIG.smart_gaps {
+                0
+            } else {
+                CONFIG.outer_gap
+            };
+            return vec![(windows[0], tiling_area(screen, dock_area, gap))];
+        }
+
+        let area = tiling_area(screen, dock_area, CONFIG.outer_gap);
+        let (pos, size) = (area.get_position(), area.get_size());
+        let default_width = (size.width - CONFIG.inner_gap) / 2;
+        let mut x = 0;
+        let columns = windows
+            .iter()
+            .map(|w| {
+                let width = self
+                    .widths
+                    .borrow()
+                    .get(w)
+                    .copied()
+                    .unwrap_or(default_width);
+                let column = (*w, x, width);
+                x += width + CONFIG.inner_gap;
+                column
+            })
+            .collect::<Vec<(Window, i32, i32)>>();
+
+        // Don't leave empty space at the end of the strip
+        let strip_width = x - CONFIG.inner_gap;
+        let mut scroll = self.scroll.get().min(strip_width - size.width).max(0);
+        if let Some((_, x, width)) = columns.iter().find(|(w, _, _)| *w == self.focus.get()) {
+            if *x < scroll {
+                scroll = *x;
+            } else if x + width > scroll + size.width {
+                scroll = x + width - size.width;
+            }
+        }
+        self.scroll.set(scroll);
+
+        columns
+            .into_iter()
+            .map(|(w, x, width)| {
+                (
+                    w,
+                    inside_border(Rect::new(
+                        Position::new(pos.x + x - scroll, pos.y),
+                        Size {
+                            width,
+                            height: size.height,
+                        },
+                    )),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Whether any of `rect` can be seen on `screen`, columns that can't are hidden
+pub fn is_visible(rect: &Rect, screen: &Screen) -> bool {
+    let (pos, size) = (rect.get_position(), rect.get_size());
+    pos.x < screen.x + screen.width && pos.x + size.width > screen.x
+}
+
+impl Default for Scrolling {
+    fn default() -> Self {
+        Self {
+            layout_type: LayoutTag::Scrolling,
+            widths: RefCell::new(HashMap::new()),
+            scroll: Cell::new(0),
+            focus: Cell::new(0),
+            last_screen: RefCell::new(None),
+        }
+    }
+}
+
+impl std::fmt::Display for Scrolling {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Scrolling {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn set_focus(&mut self, w: Window) {
+        self.focus.set(w);
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        // The new window gets focus, so the strip scrolls to it
+        self.focus.set(w);
+        let windows = windows
+            .into_iter()
+            .filter(|ww| !ww.is_trans && ww.window() != w)
+            .map(|ww| ww.window())
+            .chain(std::iter::once(w))
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        column_master::ColumnMaster::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn resize(
+        &self,
+        win: Window,
+        axis: &Axis,
+        delta: i32,
+        windows: &[&WindowWrapper],
+    ) -> Vec<WindowWrapper> {
+        if let Some(ww) = windows.iter().find(|ww| ww.is_trans && ww.window() == win) {
+            let size = ww.get_size();
+            let size = match axis {
+                Axis::Horizontal => Size {
+                    width: size.width + delta,
+                    height: size.height,
+                },
+                Axis::Vertical => Size {
+                    width: size.width,
+                    height: size.height + delta,
+                },
+            };
+            return vec![WindowWrapper {
+                window_rect: Rect::new(ww.get_position(), size),
+                ..(**ww).clone()
+            }];
+        }
+
+        // Columns are always full height, only their width can change
+        let tiled = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        if *axis == Axis::Vertical || tiled.len() < 2 || !tiled.contains(&win) {
+            return vec![];
+        }
+        let (screen, dock_area) = match self.last_screen.borrow().clone() {
+            Some(last) => last,
+            None => return vec![],
+        };
+        let area = tiling_area(&screen, &dock_area, CONFIG.outer_gap);
+        let width = self
+            .widths
+            .borrow()
+            .get(&win)
+            .copied()
+            .unwrap_or((area.get_size().width - CONFIG.inner_gap) / 2);
+        let min = 2 * CONFIG.border_width + 1;
+        self.widths
+            .borrow_mut()
+            .insert(win, (width + delta).clamp(min, area.get_size().width));
+
+        self.focus.set(win);
+        self.arrange(&screen, &dock_area, &tiled)
+            .into_iter()
+            .filter_map(|(w, rect)| {
+                let ww = windows.iter().find(|ww| ww.window() == w)?;
+                Some(WindowWrapper {
+                    window_rect: rect,
+                    ..**ww
+                })
+            })
+            .collect()
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        self.focus.set(focus);
+        let windows = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        if windows.is_empty() {
+            return vec![];
+        }
+        self.arrange(screen, dock_area, &windows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_visible, Scrolling};
+    use crate::{
+        layout::{inside_border, Layout},
+        models::{dockarea::DockArea, rect::Rect, screen::Screen},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        inside_border(Rect::new(Position::new(x, y), Size { width, height }))
+    }
+
+    #[test]
+    fn strip_scrolls_to_focus() {
+        let screen = Screen::new(0, 1000, 800, 0, 0);
+        let mut layout = Scrolling::default();
+        layout.set_focus(3);
+        let tested = layout.arrange(&screen, &DockArea::default(), &[1, 2, 3]);
+        assert_eq!(
+            vec![
+                (1, rect(-500, 0, 500, 800)),
+                (2, rect(0, 0, 500, 800)),
+                (3, rect(500, 0, 500, 800)),
+            ],
+            tested
+        );
+        assert!(!is_visible(&tested[0].1, &screen));
+        assert!(is_visible(&tested[2].1, &screen));
+
+        // Going back left only scrolls as far as needed
+        layout.set_focus(1);
+        let tested = layout.arrange(&screen, &DockArea::default(), &[1, 2, 3]);
+        assert_eq!((1, rect(0, 0, 500, 800)), tested[0]);
+        assert!(!is_visible(&tested[2].1, &screen));
+    }
+}
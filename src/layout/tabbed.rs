@@ -0,0 +1,194 @@
+#![allow(dead_code, unused_variables)]
+use super::*;
+use crate::{
+    config::*,
+    models::{dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper},
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
+};
+
+/// Every window takes the whole work area below a strip listing their titles.
+/// Tabbed puts the titles side by side, Stacked puts them in rows.
+#[derive(Debug, Clone)]
+pub struct Tabbed {
+    pub layout_type: LayoutTag,
+    stacked: bool,
+}
+
+impl Tabbed {
+    pub fn stacked() -> Self {
+        Self {
+            layout_type: LayoutTag::Stacked,
+            stacked: true,
+        }
+    }
+
+    fn arrange(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: &[Window],
+    ) -> Vec<(Window, Rect)> {
+        let (_, client_area) = areas(screen, dock_area, windows.len(), self.stacked);
+        let rect = inside_border(client_area);
+        windows.iter().map(|w| (*w, rect)).collect()
+    }
+}
+
+/// The tab strip and the area below it for `count` windows
+pub fn areas(screen: &Screen, dock_area: &DockArea, count: usize, stacked: bool) -> (Rect, Rect) {
+    let gap = if CONFIG.smart_gaps && count == 1 {
+        0
+    } else {
+        CONFIG.outer_gap
+    };
+    let area = tiling_area(screen, dock_area, gap);
+    let height = if stacked {
+        CONFIG.decoration_height * count.max(1) as i32
+    } else {
+        CONFIG.decoration_height
+    };
+    split_rect(area, &Axis::Vertical, height, 0)
+}
+
+/// One rect per tab inside `strip`, relative to the strip
+pub fn tab_rects(strip: Rect, count: usize, stacked: bool) -> Vec<Rect> {
+    let size = strip.get_size();
+    let strip = Rect::new(Position::new(0, 0), size);
+    let axis = if stacked {
+        Axis::Vertical
+    } else {
+        Axis::Horizontal
+    };
+    split_even(strip, &axis, count, 0)
+}
+
+impl Default for Tabbed {
+    fn default() -> Self {
+        Self {
+            layout_type: LayoutTag::Tabbed,
+            stacked: false,
+        }
+    }
+}
+
+impl std::fmt::Display for Tabbed {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.layout_type)
+    }
+}
+
+impl Layout for Tabbed {
+    fn get_type(&self) -> LayoutTag {
+        self.layout_type.clone()
+    }
+
+    fn place_window(
+        &mut self,
+        dock_area: &DockArea,
+        screen: &Screen,
+        w: Window,
+        windows: Vec<&WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .into_iter()
+            .filter(|ww| !ww.is_trans && ww.window() != w)
+            .map(|ww| ww.window())
+            .chain(std::iter::once(w))
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
+    }
+
+    fn move_window(
+        &self,
+        screen: &Screen,
+        dock_area: &DockArea,
+        w: Window,
+        respect_dock: bool,
+        x: i32,
+        y: i32,
+    ) -> (Position, Position) {
+        column_master::ColumnMaster::default().move_window(screen, dock_area, w, respect_dock, x, y)
+    }
+
+    fn resize(
+        &self,
+        win: Window,
+        axis: &Axis,
+        delta: i32,
+        windows: &[&WindowWrapper],
+    ) -> Vec<WindowWrapper> {
+        // Only dialogs can be resized, the tabs always fill the screen
+        windows
+            .iter()
+            .filter(|ww| ww.is_trans && ww.window() == win)
+            .map(|ww| {
+                let size = ww.get_size();
+                let size = match axis {
+                    Axis::Horizontal => Size {
+                        width: size.width + delta,
+                        height: size.height,
+                    },
+                    Axis::Vertical => Size {
+                        width: size.width,
+                        height: size.height + delta,
+                    },
+                };
+                WindowWrapper {
+                    window_rect: Rect::new(ww.get_position(), size),
+                    ..(**ww).clone()
+                }
+            })
+            .collect()
+    }
+
+    fn reorder(
+        &mut self,
+        focus: Window,
+        screen: &Screen,
+        dock_area: &DockArea,
+        windows: Vec<WindowWrapper>,
+    ) -> Vec<(Window, Rect)> {
+        let windows = windows
+            .iter()
+            .filter(|ww| !ww.is_trans)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        self.arrange(screen, dock_area, &windows)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{areas, tab_rects, Tabbed};
+    use crate::{
+        config::CONFIG,
+        layout::inside_border,
+        models::{dockarea::DockArea, rect::Rect, screen::Screen},
+        xlibwrapper::util::{Position, Size},
+    };
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect::new(Position::new(x, y), Size { width, height })
+    }
+
+    #[test]
+    fn windows_share_area_below_strip() {
+        let screen = Screen::new(0, 1000, 800, 0, 0);
+        let bar = CONFIG.decoration_height;
+        let (strip, _) = areas(&screen, &DockArea::default(), 2, true);
+        assert_eq!(rect(0, 0, 1000, 2 * bar), strip);
+
+        let client = inside_border(rect(0, bar, 1000, 800 - bar));
+        assert_eq!(
+            vec![(1, client), (2, client)],
+            Tabbed::default().arrange(&screen, &DockArea::default(), &[1, 2])
+        );
+        assert_eq!(
+            vec![rect(0, 0, 500, bar), rect(500, 0, 500, bar)],
+            tab_rects(rect(0, 0, 1000, bar), 2, false)
+        );
+    }
+}
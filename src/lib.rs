@@ -0,0 +1,17 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+
+pub mod config;
+pub mod hdl_dispatcher;
+mod hdl_reactor;
+pub mod ipc;
+pub mod layout;
+pub mod models;
+mod reducers;
+mod state;
+mod wm;
+pub mod xlibwrapper;
+
+pub type HadlockResult<T> = Result<T, Box<dyn std::error::Error>>;
+pub type HadlockOption<T> = Option<T>;
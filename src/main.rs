@@ -1,27 +1,17 @@
-#[allow(unused_imports)]
 #[macro_use]
 extern crate log;
 
-mod config;
-mod hdl_dispatcher;
-mod hdl_reactor;
-mod layout;
-mod models;
-mod reducers;
-mod state;
-mod wm;
-mod xlibwrapper;
-
 use std::{process::Command, rc::Rc, sync::mpsc, thread};
-use xlibwrapper::{core::*, DisplayServer};
 
-use crate::config::*;
+use hadlock::{
+    config::*,
+    hdl_dispatcher,
+    xlibwrapper::{core::*, DisplayServer},
+    HadlockResult,
+};
 use lazy_static::initialize;
 use nix::sys::signal::{self, SigHandler, Signal};
 
-pub type HadlockResult<T> = Result<T, Box<dyn std::error::Error>>;
-pub type HadlockOption<T> = Option<T>;
-
 fn main() -> HadlockResult<()> {
     init_logger()?;
     initialize(&CONFIG);
@@ -41,6 +41,11 @@ impl From<&[i64]> for DockArea {
 }
 
 impl DockArea {
+    /// Space reserved at the edges of the screen as (top, bottom, left, right)
+    pub fn reserved(&self) -> (i32, i32, i32, i32) {
+        (self.top, self.bottom, self.left, self.right)
+    }
+
     pub fn as_rect(&self, s: &Screen) -> Option<Rect> {
         let screen_width = s.width as i32;
         let screen_height = s.height as i32;
@@ -8,7 +8,7 @@ pub mod workspace;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
 pub enum WindowState {
     Snapped(Direction),
     Maximized,
@@ -29,3 +29,21 @@ pub enum Direction {
     West,
     NorthWest,
 }
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "").as_str() {
+            "north" => Ok(Direction::North),
+            "northeast" => Ok(Direction::NorthEast),
+            "east" => Ok(Direction::East),
+            "southeast" => Ok(Direction::SouthEast),
+            "south" => Ok(Direction::South),
+            "southwest" => Ok(Direction::SouthWest),
+            "west" => Ok(Direction::West),
+            "northwest" => Ok(Direction::NorthWest),
+            _ => Err(format!("{} is not a valid direction", s)),
+        }
+    }
+}
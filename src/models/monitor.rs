@@ -4,7 +4,7 @@ use super::{
 };
 use crate::{
     config::Axis,
-    layout::LayoutTag,
+    layout::{scrolling, LayoutTag},
     xlibwrapper::{
         util::{Position, Size},
         xlibmodels::{MonitorId, Window},
@@ -19,6 +19,8 @@ pub struct Monitor {
     pub workspaces: HashMap<u32, Workspace>,
     pub dock_area: DockArea,
     pub current_ws: u32,
+    /// Workspaces this monitor showed before, most recent first
+    pub ws_history: Vec<u32>,
 }
 
 impl Monitor {
@@ -36,6 +38,7 @@ impl Monitor {
             workspaces,
             dock_area: Default::default(),
             current_ws,
+            ws_history: vec![],
         }
     }
 
@@ -169,6 +172,32 @@ impl Monitor {
         Some(())
     }
 
+    /// Remembers that the monitor is about to leave its current workspace for `next`
+    pub fn push_ws_history(&mut self, next: u32) {
+        let current = self.current_ws;
+        self.ws_history.retain(|ws| *ws != current && *ws != next);
+        self.ws_history.insert(0, current);
+    }
+
+    /// The workspace after (or before) the current one among those with windows
+    pub fn cycle_occupied_ws(&self, forward: bool) -> Option<u32> {
+        let mut occupied = self
+            .workspaces
+            .values()
+            .filter(|ws| !ws.clients.is_empty() || ws.tag == self.current_ws)
+            .map(|ws| ws.tag)
+            .collect::<Vec<u32>>();
+        occupied.sort_unstable();
+        let index = occupied.iter().position(|ws| *ws == self.current_ws)?;
+        let len = occupied.len();
+        let index = if forward {
+            (index + 1) % len
+        } else {
+            (index + len - 1) % len
+        };
+        Some(occupied[index])
+    }
+
     pub fn get_client_keys(&self) -> Vec<Window> {
         self.workspaces
             .values()
@@ -189,11 +218,45 @@ impl Monitor {
         self.workspaces.get(&self.current_ws)?.clients.get(&w)
     }
 
+    /// Removes the sticky windows from the current workspace so they can follow to the next one
+    pub fn take_sticky(&mut self) -> Vec<WindowWrapper> {
+        let ws = match self.workspaces.get_mut(&self.current_ws) {
+            Some(ws) => ws,
+            None => return vec![],
+        };
+        let sticky = ws
+            .clients
+            .values()
+            .filter(|ww| ww.sticky)
+            .map(|ww| ww.window())
+            .collect::<Vec<Window>>();
+        sticky
+            .into_iter()
+            .filter_map(|w| ws.remove_window(w))
+            .collect()
+    }
+
+    /// Columns scrolled out of view are moved away like the windows of hidden workspaces,
+    /// other layouts bring them back. Hidden `scratchpads` are left alone.
+    pub fn hide_off_screen(&mut self, scratchpads: &[Window]) {
+        let scrolling = self.get_current_layout() == LayoutTag::Scrolling;
+        let screen = self.screen.clone();
+        if let Some(ws) = self.get_current_ws_mut() {
+            ws.apply_to_all(|ww| {
+                if ww.is_trans || scratchpads.contains(&ww.window()) {
+                    return;
+                }
+                ww.hidden = scrolling && !scrolling::is_visible(&ww.window_rect, &screen);
+            });
+        }
+    }
+
     // Layout functions
     pub fn place_window(&mut self, w: Window) -> Vec<(Window, Rect)> {
         let screen = self.screen.clone();
         let dock_area = self.dock_area.clone();
         let ws = self.get_current_ws_mut().expect("monitor: place_window 2");
+        ws.layout.set_focus(ws.focus_w);
         let windows = ws.clients.values().collect::<Vec<&WindowWrapper>>();
         ws.layout.place_window(&dock_area, &screen, w, windows)
     }
@@ -266,6 +329,7 @@ impl std::fmt::Display for Monitor {
 
 #[cfg(test)]
 mod test {
+    use crate::layout::LayoutTag;
     use crate::models::{
         monitor::Monitor, rect::*, screen::Screen, windowwrapper::*, workspace::Workspace,
         WindowState,
@@ -536,4 +600,84 @@ mod test {
 
         assert_eq!(None, mon.get_client(tested.window()))
     }
+
+    #[test]
+    fn cycle_occupied_ws_skips_empty() {
+        let mut mon = setup_mon(4);
+        let ww = WindowWrapper::new(
+            1,
+            Rect::new(
+                Position { x: 0, y: 0 },
+                Size {
+                    width: 200,
+                    height: 200,
+                },
+            ),
+            false,
+        );
+        mon.add_window_non_current(ww.window(), ww, 2);
+        assert_eq!(Some(2), mon.cycle_occupied_ws(true));
+        assert_eq!(Some(2), mon.cycle_occupied_ws(false));
+
+        mon.push_ws_history(2);
+        mon.current_ws = 2;
+        // Workspace 0 was left empty
+        assert_eq!(Some(2), mon.cycle_occupied_ws(true));
+        assert_eq!(vec![0], mon.ws_history);
+    }
+
+    #[test]
+    fn take_sticky_leaves_other_windows() {
+        let mut mon = setup_mon(1);
+        let rect = Rect::new(
+            Position { x: 0, y: 0 },
+            Size {
+                width: 200,
+                height: 200,
+            },
+        );
+        let tiled = WindowWrapper::new(1, rect, false);
+        let sticky = WindowWrapper {
+            window: 2,
+            sticky: true,
+            ..WindowWrapper::new(2, rect, true)
+        };
+        mon.add_window(tiled.window(), tiled);
+        mon.add_window(sticky.window(), sticky.clone());
+
+        assert_eq!(vec![sticky], mon.take_sticky());
+        assert!(mon.get_client(2).is_none());
+        assert!(mon.get_client(1).is_some());
+    }
+
+    #[test]
+    fn leaving_scrolling_shows_columns() {
+        let mut mon = setup_mon(1);
+        let ws = mon.get_current_ws_mut().unwrap();
+        while ws.get_current_layout() != LayoutTag::Scrolling {
+            ws.cycle_layout();
+        }
+        // Far to the right of the screen
+        let rect = Rect::new(
+            Position { x: 5000, y: 0 },
+            Size {
+                width: 200,
+                height: 200,
+            },
+        );
+        let column = WindowWrapper::new(1, rect, false);
+        let scratchpad = WindowWrapper {
+            hidden: true,
+            ..WindowWrapper::new(2, rect, false)
+        };
+        mon.add_window(column.window(), column);
+        mon.add_window(scratchpad.window(), scratchpad);
+        mon.hide_off_screen(&[2]);
+        assert!(mon.get_client(1).unwrap().hidden);
+
+        mon.get_current_ws_mut().unwrap().cycle_layout();
+        mon.hide_off_screen(&[2]);
+        assert!(!mon.get_client(1).unwrap().hidden);
+        assert!(mon.get_client(2).unwrap().hidden);
+    }
 }
@@ -1,6 +1,7 @@
 #![allow(unreachable_patterns)]
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum WindowType {
     Desktop,
     Dock,
@@ -9,6 +9,8 @@ pub struct WindowWrapper {
     pub window_rect: Rect,
     pub hidden: bool,
     pub is_trans: bool,
+    /// Shown on every workspace of its monitor
+    pub sticky: bool,
     pub restore_position: Position,
     pub restore_size: Size,
     pub current_state: WindowState,
@@ -24,6 +26,7 @@ impl WindowWrapper {
             window_rect,
             hidden: false,
             is_trans,
+            sticky: false,
             restore_position: Position { x: 0, y: 0 },
             restore_size,
             current_state: WindowState::Free,
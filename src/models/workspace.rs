@@ -3,7 +3,7 @@ use indexmap::IndexMap;
 
 use crate::{
     config::*,
-    layout::{self, column_master, floating, Layout, LayoutTag},
+    layout::{self, column_master, Layout, LayoutTag},
     models::windowwrapper::WindowWrapper,
     xlibwrapper::xlibmodels::Window,
 };
@@ -14,24 +14,63 @@ pub struct Workspace {
     pub clients: IndexMap<Window, WindowWrapper>,
     pub layout: Box<dyn Layout>,
     pub focus_w: Window,
+    /// Minimized windows, oldest first, they are kept out of the layout until restored
+    pub minimized: Vec<WindowWrapper>,
+    /// Kept here rather than in the layout so they survive cycling layouts
+    pub master_count: usize,
+    pub master_ratio: f32,
     available_layouts: Vec<LayoutTag>,
     current_tag: LayoutTag,
 }
 
 impl Workspace {
     pub fn new(tag: u32, focus_w: Window) -> Self {
-        Self {
+        let mut ws = Self {
             tag,
             clients: Default::default(),
-            layout: layout::layout_from_tag(CONFIG.default_layout),
+            layout: layout::layout_from_tag(CONFIG.default_layout.clone()),
             focus_w,
-            available_layouts: vec![LayoutTag::Floating, LayoutTag::ColumnMaster],
-            current_tag: CONFIG.default_layout,
-        }
+            minimized: vec![],
+            master_count: CONFIG.master_count,
+            master_ratio: CONFIG.master_ratio,
+            available_layouts: available_layouts(),
+            current_tag: CONFIG.default_layout.clone(),
+        };
+        ws.set_master(ws.master_count, ws.master_ratio);
+        ws
     }
 
     pub fn get_current_layout(&self) -> LayoutTag {
-        self.current_tag
+        self.current_tag.clone()
+    }
+
+    /// Picks up added or removed layout scripts
+    pub fn refresh_layouts(&mut self) {
+        self.available_layouts = available_layouts();
+        if let LayoutTag::Scripted(_) = self.current_tag {
+            self.set_layout(self.current_tag.clone());
+        }
+    }
+
+    pub fn set_master(&mut self, count: usize, ratio: f32) {
+        self.master_count = count;
+        self.master_ratio = ratio.clamp(column_master::MIN_RATIO, column_master::MAX_RATIO);
+        self.layout.set_master(self.master_count, self.master_ratio);
+    }
+
+    pub fn change_master_count(&mut self, delta: i32) {
+        let count = (self.master_count as i32 + delta).max(0) as usize;
+        self.set_master(count, self.master_ratio);
+    }
+
+    pub fn change_master_ratio(&mut self, delta: f32) {
+        self.set_master(self.master_count, self.master_ratio + delta);
+    }
+
+    fn set_layout(&mut self, tag: LayoutTag) {
+        self.layout = layout::layout_from_tag(tag.clone());
+        self.layout.set_master(self.master_count, self.master_ratio);
+        self.current_tag = tag;
     }
 
     pub fn cycle_layout(&mut self) {
@@ -39,15 +78,10 @@ impl Workspace {
             .available_layouts
             .iter()
             .position(|lt| self.current_tag == *lt)
-            .unwrap()
-            + 1;
+            .map_or(0, |index| index + 1);
 
         let index = index % self.available_layouts.len();
-        self.current_tag = self.available_layouts[index];
-        self.layout = match self.current_tag {
-            LayoutTag::Floating => Box::new(floating::Floating::default()),
-            LayoutTag::ColumnMaster => Box::new(column_master::ColumnMaster::default()),
-        }
+        self.set_layout(self.available_layouts[index].clone());
     }
 
     pub fn contains_window(&self, w: Window) -> bool {
@@ -135,10 +169,39 @@ impl PartialEq for Workspace {
 
 impl Eq for Workspace {}
 
+fn available_layouts() -> Vec<LayoutTag> {
+    vec![
+        LayoutTag::Floating,
+        LayoutTag::ColumnMaster,
+        LayoutTag::RightMaster,
+        LayoutTag::TopMaster,
+        LayoutTag::BottomMaster,
+        LayoutTag::CenteredMaster,
+        LayoutTag::Dwindle,
+        LayoutTag::Spiral,
+        LayoutTag::Bsp,
+        LayoutTag::Grid,
+        LayoutTag::Tabbed,
+        LayoutTag::Stacked,
+        LayoutTag::Scrolling,
+    ]
+    .into_iter()
+    .chain(
+        layout::scripted::names()
+            .into_iter()
+            .map(LayoutTag::Scripted),
+    )
+    .collect()
+}
+
 #[cfg(test)]
 mod test {
     use crate::layout::{self, LayoutTag};
-    use crate::models::workspace::Workspace;
+    use crate::models::{
+        dockarea::DockArea, rect::Rect, screen::Screen, windowwrapper::WindowWrapper,
+        workspace::Workspace,
+    };
+    use crate::xlibwrapper::util::{Position, Size};
 
     #[test]
     fn cycle_layout() {
@@ -146,6 +209,9 @@ mod test {
             tag: 0,
             focus_w: 0,
             clients: Default::default(),
+            minimized: vec![],
+            master_count: 1,
+            master_ratio: 0.5,
             layout: layout::layout_from_tag(LayoutTag::Floating),
             available_layouts: vec![LayoutTag::Floating, LayoutTag::ColumnMaster],
             current_tag: LayoutTag::Floating,
@@ -154,4 +220,44 @@ mod test {
         ws.cycle_layout();
         assert_eq!(LayoutTag::ColumnMaster, ws.layout.get_type())
     }
+
+    #[test]
+    fn master_count_survives_cycling() {
+        let mut ws = Workspace {
+            tag: 0,
+            focus_w: 0,
+            clients: Default::default(),
+            minimized: vec![],
+            master_count: 1,
+            master_ratio: 0.5,
+            layout: layout::layout_from_tag(LayoutTag::ColumnMaster),
+            available_layouts: vec![LayoutTag::ColumnMaster, LayoutTag::RightMaster],
+            current_tag: LayoutTag::ColumnMaster,
+        };
+        ws.change_master_count(1);
+        ws.cycle_layout();
+
+        let windows = (1..=3)
+            .map(|w| {
+                WindowWrapper::new(
+                    w,
+                    Rect::new(
+                        Position::new(0, 0),
+                        Size {
+                            width: 100,
+                            height: 100,
+                        },
+                    ),
+                    false,
+                )
+            })
+            .collect::<Vec<WindowWrapper>>();
+        let screen = Screen::new(0, 1000, 800, 0, 0);
+        let mut fresh = layout::layout_from_tag(LayoutTag::RightMaster);
+        assert_eq!(2, ws.master_count);
+        assert_ne!(
+            fresh.reorder(1, &screen, &DockArea::default(), windows.clone()),
+            ws.layout.reorder(1, &screen, &DockArea::default(), windows)
+        )
+    }
 }
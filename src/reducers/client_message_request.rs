@@ -1,4 +1,12 @@
-use crate::{layout::LayoutTag, state::State, wm, xlibwrapper::action};
+use crate::{
+    models::WindowState,
+    state::State,
+    wm,
+    xlibwrapper::{
+        action,
+        xlibmodels::{ALL_DESKTOPS, ICONIC_STATE},
+    },
+};
 use reducer::*;
 
 #[allow(clippy::collapsible_if)]
@@ -25,23 +33,60 @@ impl Reducer<action::ClientMessageRequest> for State {
             .expect("client_message_request: cleanupt");
         // debug!("data_two: {:?}", data_two);
 
-        if action.message_type == self.lib.xatom().NetWMState {
-            if data_zero == self.lib.xatom().NetWMStateHidden as i64
-                || data_one == self.lib.xatom().NetWMStateHidden as i64
-                || data_two == self.lib.xatom().NetWMStateHidden as i64
-            {
-                debug!("window: 0x{:x} sent a hidden message", action.win);
-                let mon_id =
-                    wm::get_mon_by_window(&self, action.win).unwrap_or(self.current_monitor);
-                let mon = self
-                    .monitors
-                    .get_mut(&mon_id)
-                    .expect("No monitor was found?!");
-                mon.remove_window(action.win);
-                if mon.get_current_layout() != LayoutTag::Floating {
-                    wm::reorder(self);
+        let hidden_atom = self.lib.xatom().NetWMStateHidden as i64;
+        if action.message_type == self.lib.xatom().NetWMState
+            && (data_one == hidden_atom || data_two == hidden_atom)
+        {
+            debug!("window: 0x{:x} sent a hidden message", action.win);
+            let minimized = self.minimized().contains_key(&action.win);
+            match data_zero {
+                0 => wm::restore_minimized(self, action.win),
+                1 => wm::minimize(self, action.win),
+                2 if minimized => wm::restore_minimized(self, action.win),
+                2 => wm::minimize(self, action.win),
+                _ => None,
+            };
+        }
+
+        if action.message_type == self.lib.xatom().WMChangeState && data_zero == ICONIC_STATE as i64
+        {
+            wm::minimize(self, action.win);
+        }
+
+        let sticky_atom = self.lib.xatom().NetWMStateSticky as i64;
+        if action.message_type == self.lib.xatom().NetWMState
+            && (data_one == sticky_atom || data_two == sticky_atom)
+        {
+            match data_zero {
+                0 => wm::set_sticky(self, action.win, false),
+                1 => wm::set_sticky(self, action.win, true),
+                2 => wm::toggle_sticky(self, action.win),
+                _ => None,
+            };
+        }
+
+        if action.message_type == self.lib.xatom().NetActiveWindow {
+            wm::activate_window(self, action.win);
+        }
+
+        if action.message_type == self.lib.xatom().NetCloseWindow {
+            wm::close_window(self, action.win);
+        }
+
+        if action.message_type == self.lib.xatom().NetWMDesktop {
+            match data_zero as u32 {
+                ALL_DESKTOPS => wm::set_sticky(self, action.win, true),
+                ws => {
+                    wm::set_sticky(self, action.win, false);
+                    wm::send_to_ws(self, action.win, ws)
                 }
-            }
+            };
+        }
+
+        // Asked before mapping, so the window isn't managed yet
+        if action.message_type == self.lib.xatom().NetRequestFrameExtents {
+            self.lib
+                .set_frame_extents(action.win, wm::frame_extents(WindowState::Free));
         }
 
         if action.message_type == self.lib.xatom().NetCurrentDesktop {
@@ -13,6 +13,8 @@ impl Reducer<action::Destroy> for State {
             return;
         }
 
+        wm::forget_minimized(self, action.win);
+
         let mon = self
             .monitors
             .get_mut(&self.current_monitor)
@@ -0,0 +1,13 @@
+use {
+    crate::{state::State, xlibwrapper::action},
+    reducer::*,
+};
+
+impl Reducer<action::Expose> for State {
+    fn reduce(&mut self, action: action::Expose) {
+        // Clients draw themselves, only the tab strips are ours
+        if !self.clients().contains_key(&action.win) {
+            self.title_generation += 1;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use {
+    super::key_press::handle_key_effect,
+    crate::{config, state::State, xlibwrapper::action},
+    reducer::*,
+};
+
+impl Reducer<action::IpcCommand> for State {
+    fn reduce(&mut self, action: action::IpcCommand) {
+        debug!("IpcCommand: {:?}", action.effect);
+        self.ipc_command_failed = match action.workspace.map(config::workspace_tag) {
+            // Workspaces are numbered from 1 like in the config
+            Some(None) => true,
+            tag => handle_key_effect(self, &action.effect, tag.flatten()).is_none(),
+        };
+        if self.ipc_command_failed {
+            debug!("Something went wrong calling handle_key_effect in ipc_command");
+        }
+        // There is no modifier to release, CycleRecent takes the first window right away
+        self.window_switch = None;
+    }
+}
@@ -2,10 +2,13 @@
 #![allow(clippy::cognitive_complexity)]
 use {
     crate::{
-        config::{Axis, Key, KeyAction, KeyEffect, CONFIG},
-        layout::LayoutTag,
-        models::{rect::*, window_type::WindowType, windowwrapper::*, Direction, WindowState},
-        state::State,
+        config::{self, Axis, Key, KeyAction, KeyEffect, CONFIG},
+        layout::{dwindle, grid, LayoutTag},
+        models::{
+            monitor::Monitor, rect::*, window_type::WindowType, windowwrapper::*, Direction,
+            WindowState,
+        },
+        state::{State, WindowSwitch},
         wm,
         xlibwrapper::action,
         xlibwrapper::core::*,
@@ -32,17 +35,19 @@ impl Reducer<action::KeyPress> for State {
             .expect("failed to convert action.keycode to KeySym");
         //debug!("KeyCode to string: {:?}", into_hdl_keysym(&sym));
 
+        // The modifier was released without us seeing it, take the window reached so far
+        if !mod_is_pressed && self.window_switch.is_some() {
+            self.window_switch = None;
+        }
+
         // Valid key presses must either include mod_key or be one of the XF86 symbols.
         if !mod_is_pressed && !sym.starts_with("XF86") {
             return;
         }
 
-        let ws_keys: Vec<u8> = (1..=9)
-            .map(|x| {
-                self.lib
-                    .str_to_keycode(&x.to_string())
-                    .expect("key_press 1")
-            })
+        let ws_keys: Vec<(u8, u32)> = config::number_keys(&CONFIG.workspaces)
+            .into_iter()
+            .filter_map(|(key, ws)| Some((self.lib.str_to_keycode(&key)?, ws)))
             .collect();
 
         let mon = match self.monitors.get_mut(&self.current_monitor) {
@@ -53,6 +58,7 @@ impl Reducer<action::KeyPress> for State {
             }
         };
 
+        let switching = self.window_switch.is_some();
         match mon.get_client(self.focus_w) {
             Some(_) => {
                 debug!("managed client");
@@ -63,16 +69,28 @@ impl Reducer<action::KeyPress> for State {
             }
             None => {}
         }
+        if !switching && self.window_switch.is_some() {
+            grab_for_window_switch(self);
+        }
+    }
+}
+
+/// Releasing the modifier is only reported while the keyboard is grabbed.
+/// If it was let go before the grab took effect the switch is committed right away.
+fn grab_for_window_switch(state: &mut State) {
+    state.lib.grab_keyboard(state.lib.get_root());
+    if state.lib.modifier_state() & CONFIG.mod_key != CONFIG.mod_key {
+        debug!("Modifier released before the keyboard was grabbed");
+        state.lib.ungrab_keyboard();
+        state.window_switch = None;
     }
 }
 
-fn handle_key_effect(
+pub(super) fn handle_key_effect(
     state: &mut State,
-    action: &action::KeyPress,
     effect: &KeyEffect,
-    ws_keys: &[u8],
+    ws_num: Option<u32>,
 ) -> Option<()> {
-    let keycode = action.keycode as u8;
     match effect {
         KeyEffect::Kill => {
             let ww = state
@@ -176,16 +194,63 @@ fn handle_key_effect(
                 }
             }
         }
+        KeyEffect::FocusNext => {
+            wm::cycle_focus(state, true);
+        }
+        KeyEffect::FocusPrev => {
+            wm::cycle_focus(state, false);
+        }
+        KeyEffect::Focus(direction) => {
+            wm::focus_direction(state, *direction);
+        }
+        KeyEffect::CycleRecent => {
+            cycle_recent(state);
+        }
+        KeyEffect::SwapWindow(direction) => {
+            swap_window(state, *direction);
+        }
         KeyEffect::ChangeCurrentWorkspace => {
-            if ws_keys.contains(&keycode) {
-                let ws_num = keycode_to_ws(keycode);
+            if let Some(ws_num) = ws_num {
+                let mon = state.monitors.get(&state.current_monitor)?;
+                let ws_num = match mon.ws_history.first() {
+                    Some(previous)
+                        if CONFIG.workspace_back_and_forth && ws_num == mon.current_ws =>
+                    {
+                        *previous
+                    }
+                    _ => ws_num,
+                };
                 wm::set_current_ws(state, ws_num);
                 state.mouse_follow.replace(true);
             }
         }
+        KeyEffect::ChangeWorkspace(num) => {
+            let ws_num = config::workspace_tag(*num)?;
+            return handle_key_effect(state, &KeyEffect::ChangeCurrentWorkspace, Some(ws_num));
+        }
+        KeyEffect::MoveWindowToWorkspace(num) => {
+            let ws_num = config::workspace_tag(*num)?;
+            return handle_key_effect(state, &KeyEffect::MoveToWorkspace, Some(ws_num));
+        }
+        KeyEffect::PreviousWorkspace => {
+            let ws_num = *state
+                .monitors
+                .get(&state.current_monitor)?
+                .ws_history
+                .first()?;
+            wm::set_current_ws(state, ws_num);
+            state.mouse_follow.replace(true);
+        }
+        KeyEffect::NextWorkspace | KeyEffect::PrevWorkspace => {
+            let ws_num = state
+                .monitors
+                .get(&state.current_monitor)?
+                .cycle_occupied_ws(*effect == KeyEffect::NextWorkspace)?;
+            wm::set_current_ws(state, ws_num);
+            state.mouse_follow.replace(true);
+        }
         KeyEffect::MoveToWorkspace => {
-            if ws_keys.contains(&keycode) {
-                let ws_num = keycode_to_ws(keycode);
+            if let Some(ws_num) = ws_num {
                 wm::move_to_ws(state, state.focus_w, ws_num);
                 if state
                     .monitors
@@ -235,6 +300,71 @@ fn handle_key_effect(
                 wm::reorder(state);
             }
         }
+        KeyEffect::Preselect(direction, percent) => {
+            let focus = state.focus_w;
+            state
+                .monitors
+                .get_mut(&state.current_monitor)?
+                .get_current_ws_mut()?
+                .layout
+                .preselect(focus, *direction, *percent as f32 / 100.0);
+        }
+        KeyEffect::Rotate => {
+            let focus = state.focus_w;
+            state
+                .monitors
+                .get_mut(&state.current_monitor)?
+                .get_current_ws_mut()?
+                .layout
+                .rotate(focus);
+            wm::reorder(state);
+        }
+        KeyEffect::Flip(axis) => {
+            let focus = state.focus_w;
+            state
+                .monitors
+                .get_mut(&state.current_monitor)?
+                .get_current_ws_mut()?
+                .layout
+                .flip(focus, axis);
+            wm::reorder(state);
+        }
+        KeyEffect::IncMasterCount(delta) => {
+            state
+                .monitors
+                .get_mut(&state.current_monitor)?
+                .get_current_ws_mut()?
+                .change_master_count(*delta);
+            wm::reorder(state);
+        }
+        KeyEffect::IncMasterRatio(delta) => {
+            state
+                .monitors
+                .get_mut(&state.current_monitor)?
+                .get_current_ws_mut()?
+                .change_master_ratio(*delta as f32 / 100.0);
+            wm::reorder(state);
+        }
+        KeyEffect::ReloadConfig => super::reload_config::reload_config(state),
+        KeyEffect::ToggleSticky => {
+            wm::toggle_sticky(state, state.focus_w);
+        }
+        KeyEffect::Minimize => {
+            wm::minimize(state, state.focus_w);
+        }
+        KeyEffect::RestoreMinimized => {
+            let w = state
+                .monitors
+                .get(&state.current_monitor)?
+                .get_current_ws()?
+                .minimized
+                .last()?
+                .window();
+            wm::restore_minimized(state, w);
+        }
+        KeyEffect::ToggleScratchpad(name) => {
+            wm::toggle_scratchpad(state, name);
+        }
         KeyEffect::Custom(command) => {
             spawn_process(&command.program, command.args.clone());
         }
@@ -247,10 +377,14 @@ fn managed_client(
     state: &mut State,
     action: action::KeyPress,
     has_mod: bool,
-    ws_keys: Vec<u8>,
+    ws_keys: Vec<(u8, u32)>,
 ) -> Option<()> {
     //debug!("Windows exists: KeyPress");
     let keycode = action.keycode as u8;
+    let ws_num = ws_keys
+        .iter()
+        .find(|(key, _)| *key == keycode)
+        .map(|(_, ws)| *ws);
 
     for key_action in CONFIG.key_bindings.iter() {
         match key_action {
@@ -263,7 +397,7 @@ fn managed_client(
                     && state.lib.str_to_keycode(key) == Some(action.keycode as u8)
                 {
                     //debug!("Effect: {:?}", effect);
-                    if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                    if handle_key_effect(state, effect, ws_num).is_none() {
                         debug!("Something went wrong calling handle_key_effect in root");
                     }
                 }
@@ -275,7 +409,7 @@ fn managed_client(
             } if !has_mod => {
                 if state.lib.str_to_keycode(key) == Some(action.keycode as u8) {
                     //debug!("Effect: {:?}", effect);
-                    if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                    if handle_key_effect(state, effect, ws_num).is_none() {
                         debug!("Something went wrong calling handle_key_effect in root");
                     }
                 }
@@ -284,10 +418,10 @@ fn managed_client(
                 mod_key: Some(mk),
                 key: Key::Number,
                 effect,
-            } if has_mod && ws_keys.contains(&keycode) => {
+            } if has_mod && ws_num.is_some() => {
                 if into_mod(mk) == (action.state & into_mod(mk)) {
                     //debug!("Effect: {:?}", effect);
-                    if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                    if handle_key_effect(state, effect, ws_num).is_none() {
                         debug!("Something went wrong calling handle_key_effect in root");
                     }
                 }
@@ -296,9 +430,9 @@ fn managed_client(
                 mod_key: None,
                 key: Key::Number,
                 effect,
-            } if !has_mod && ws_keys.contains(&keycode) => {
+            } if !has_mod && ws_num.is_some() => {
                 //debug!("Effect: {:?}", effect);
-                if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                if handle_key_effect(state, effect, ws_num).is_none() {
                     debug!("Something went wrong calling handle_key_effect in root");
                 }
             }
@@ -312,9 +446,13 @@ fn root(
     state: &mut State,
     action: action::KeyPress,
     has_mod: bool,
-    ws_keys: Vec<u8>,
+    ws_keys: Vec<(u8, u32)>,
 ) -> Option<()> {
     let keycode = action.keycode as u8;
+    let ws_num = ws_keys
+        .iter()
+        .find(|(key, _)| *key == keycode)
+        .map(|(_, ws)| *ws);
 
     for key_action in CONFIG.key_bindings.iter() {
         match key_action {
@@ -327,7 +465,7 @@ fn root(
                     && state.lib.str_to_keycode(key) == Some(action.keycode as u8)
                 {
                     debug!("Effect: {:?}", effect);
-                    if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                    if handle_key_effect(state, effect, ws_num).is_none() {
                         debug!("Something went wrong calling handle_key_effect in root");
                     }
                 }
@@ -339,7 +477,7 @@ fn root(
             } if !has_mod => {
                 if state.lib.str_to_keycode(key) == Some(action.keycode as u8) {
                     debug!("Effect: {:?}", effect);
-                    if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                    if handle_key_effect(state, effect, ws_num).is_none() {
                         debug!("Something went wrong calling handle_key_effect in root");
                     }
                 }
@@ -348,9 +486,9 @@ fn root(
                 mod_key: None,
                 key: Key::Number,
                 effect,
-            } if !has_mod && ws_keys.contains(&keycode) => {
+            } if !has_mod && ws_num.is_some() => {
                 debug!("Effect: {:?}", effect);
-                if handle_key_effect(state, &action, effect, &ws_keys).is_none() {
+                if handle_key_effect(state, effect, ws_num).is_none() {
                     debug!("Something went wrong calling handle_key_effect in root");
                 }
             }
@@ -370,6 +508,18 @@ fn shift_window(state: &mut State, direction: Direction) -> Option<()> {
     let previous = mon.get_previous(state.focus_w).map(|ww| ww.window());
     let next = mon.get_next(state.focus_w).map(|ww| ww.window());
     let current_layout = mon.get_current_layout();
+    if current_layout == LayoutTag::Grid {
+        return swap_grid_neighbour(state, direction);
+    }
+    if current_layout == LayoutTag::Dwindle || current_layout == LayoutTag::Spiral {
+        return swap_dwindle_neighbour(state, direction, current_layout == LayoutTag::Spiral);
+    }
+    if current_layout == LayoutTag::Tabbed || current_layout == LayoutTag::Stacked {
+        return cycle_tab(state, direction);
+    }
+    if current_layout == LayoutTag::Scrolling {
+        return shift_column(state, direction);
+    }
     if current_layout != LayoutTag::Floating {
         let newest = mon.get_newest().map(|(win, _)| *win)?;
 
@@ -425,25 +575,192 @@ fn shift_window(state: &mut State, direction: Direction) -> Option<()> {
 
 fn swap_master(state: &mut State) -> Option<()> {
     debug!("Swap master");
+    let focus = state.focus_w;
     let mon = state.monitors.get_mut(&state.current_monitor)?;
-    let newest = mon.get_newest()?;
-    let (win, client) = (*newest.0, newest.1.clone());
-    if win != state.focus_w {
-        let client_toc = client.toc;
-        let mut tmp_toc = std::time::Instant::now();
-        mon.swap_window(state.focus_w, |_mon, ww| WindowWrapper {
-            toc: {
-                tmp_toc = ww.toc;
-                client_toc
-            },
-            ..ww
-        })?;
-        mon.swap_window(win, |_mon, ww| WindowWrapper { toc: tmp_toc, ..ww })?;
+    let win = *mon.get_newest()?.0;
+    if win != focus {
+        swap_order(mon, focus, win)?;
         wm::reorder(state);
     }
     Some(())
 }
 
+/// Focuses the next window in the focus history, switching workspace if needed.
+/// The history is only reordered once the switch is committed on key release.
+fn cycle_recent(state: &mut State) -> Option<()> {
+    let switch = match state.window_switch.take() {
+        Some(switch) => WindowSwitch {
+            index: (switch.index + 1) % switch.windows.len(),
+            ..switch
+        },
+        None => {
+            let clients = state.clients();
+            // Hidden scratchpads are only brought back by toggling them
+            let scratchpads = state.scratchpads.values().copied().collect::<Vec<Window>>();
+            let mut windows = state
+                .focus_history
+                .borrow()
+                .iter()
+                .copied()
+                .filter(|w| clients.contains_key(w))
+                .collect::<Vec<Window>>();
+            let mut never_focused = clients
+                .values()
+                .filter(|ww| !windows.contains(&ww.window()))
+                .copied()
+                .collect::<Vec<&WindowWrapper>>();
+            never_focused.sort_by_key(|ww| std::cmp::Reverse(ww.toc));
+            windows.extend(never_focused.into_iter().map(|ww| ww.window()));
+            windows.retain(|w| {
+                !scratchpads.contains(w) || clients.get(w).is_some_and(|ww| !ww.hidden)
+            });
+            if windows.len() < 2 {
+                return None;
+            }
+            // The focused window is first, start with the one before it
+            let index = if windows[0] == state.focus_w { 1 } else { 0 };
+            WindowSwitch { windows, index }
+        }
+    };
+
+    let w = switch.windows[switch.index];
+    state.window_switch = Some(switch);
+    let mon_id = wm::get_mon_by_window(state, w)?;
+    let ws = state.monitors.get(&mon_id)?.get_ws_by_window(w)?;
+    if state.monitors.get(&mon_id)?.current_ws != ws {
+        wm::set_current_ws(state, ws)?;
+    }
+    wm::focus_window(state, mon_id, w)
+}
+
+fn swap_window(state: &mut State, direction: Direction) -> Option<()> {
+    let focus = state.focus_w;
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    let from = mon.get_client(focus)?;
+    if mon.get_current_layout() == LayoutTag::Floating || from.is_trans {
+        return None;
+    }
+    let from = from.window_rect;
+    let windows = wm::focusable(mon)
+        .into_iter()
+        .filter(|(w, _)| *w != focus && mon.get_client(*w).is_some_and(|ww| !ww.is_trans))
+        .collect::<Vec<(Window, Rect)>>();
+    match wm::nearest_in_direction(&from, &windows, direction) {
+        Some(other) => {
+            swap_order(mon, focus, other)?;
+            wm::reorder(state)
+        }
+        None => {
+            let mon_id = wm::monitor_in_direction(state, direction)?;
+            wm::move_to_monitor(state, focus, mon_id)
+        }
+    }
+}
+
+/// Swaps places with the window next to the focused one in the grid
+fn swap_grid_neighbour(state: &mut State, direction: Direction) -> Option<()> {
+    let focus = state.focus_w;
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    let windows = mon
+        .get_current_ws()?
+        .clients
+        .values()
+        .filter(|ww| !ww.is_trans)
+        .map(|ww| ww.window())
+        .collect::<Vec<Window>>();
+    let index = windows.iter().position(|w| *w == focus)?;
+    let other = windows[grid::neighbour(index, windows.len(), direction)?];
+    swap_order(mon, focus, other)?;
+    wm::reorder(state);
+    Some(())
+}
+
+/// Swaps places with the window on the other side of a split the focused one is part of
+fn swap_dwindle_neighbour(state: &mut State, direction: Direction, spiral: bool) -> Option<()> {
+    let focus = state.focus_w;
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    // Dwindle lays the newest window out first
+    let windows = mon
+        .get_current_ws()?
+        .clients
+        .values()
+        .rev()
+        .filter(|ww| !ww.is_trans)
+        .map(|ww| ww.window())
+        .collect::<Vec<Window>>();
+    let index = windows.iter().position(|w| *w == focus)?;
+    let other = windows[dwindle::neighbour(index, windows.len(), direction, spiral)?];
+    swap_order(mon, focus, other)?;
+    wm::reorder(state);
+    Some(())
+}
+
+/// East and South focus the next tab, West and North the previous one
+fn cycle_tab(state: &mut State, direction: Direction) -> Option<()> {
+    let focus = state.focus_w;
+    let ws = state
+        .monitors
+        .get_mut(&state.current_monitor)?
+        .get_current_ws_mut()?;
+    let windows = ws
+        .clients
+        .values()
+        .filter(|ww| !ww.is_trans)
+        .map(|ww| ww.window())
+        .collect::<Vec<Window>>();
+    if windows.is_empty() {
+        return None;
+    }
+    let index = windows.iter().position(|w| *w == focus).unwrap_or(0);
+    let index = match direction {
+        Direction::East | Direction::South => (index + 1) % windows.len(),
+        Direction::West | Direction::North => (index + windows.len() - 1) % windows.len(),
+        _ => return None,
+    };
+    ws.focus_w = windows[index];
+    state.focus_w = windows[index];
+    Some(())
+}
+
+/// Moves the focused column past its neighbour, the strip scrolls along with it
+fn shift_column(state: &mut State, direction: Direction) -> Option<()> {
+    let focus = state.focus_w;
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    let windows = mon
+        .get_current_ws()?
+        .clients
+        .values()
+        .filter(|ww| !ww.is_trans)
+        .map(|ww| ww.window())
+        .collect::<Vec<Window>>();
+    let index = windows.iter().position(|w| *w == focus)?;
+    let other = match direction {
+        Direction::West if index > 0 => windows[index - 1],
+        Direction::East => *windows.get(index + 1)?,
+        _ => return None,
+    };
+    swap_order(mon, focus, other)?;
+    wm::reorder(state);
+    Some(())
+}
+
+/// Tiled layouts place windows by age, so two windows trade places by trading toc.
+/// Layouts keeping their own arrangement are told about the swap as well.
+fn swap_order(mon: &mut Monitor, a: Window, b: Window) -> Option<()> {
+    let b_toc = mon.get_client(b)?.toc;
+    let mut a_toc = std::time::Instant::now();
+    mon.swap_window(a, |_mon, ww| WindowWrapper {
+        toc: {
+            a_toc = ww.toc;
+            b_toc
+        },
+        ..ww
+    })?;
+    mon.swap_window(b, |_mon, ww| WindowWrapper { toc: a_toc, ..ww })?;
+    mon.get_current_ws_mut()?.layout.swap(a, b);
+    Some(())
+}
+
 fn cycle_layout(state: &mut State) -> Option<()> {
     let mon = state.monitors.get_mut(&state.current_monitor)?;
     let ws = mon.get_current_ws_mut()?;
@@ -464,10 +781,6 @@ fn cycle_layout(state: &mut State) -> Option<()> {
     }
 }
 
-fn keycode_to_ws(keycode: u8) -> u32 {
-    ((keycode - 10) % 10) as u32
-}
-
 fn spawn_process(bin_name: &str, args: Vec<String>) {
     let mut cmd = Command::new(bin_name);
     args.into_iter().for_each(|arg| {
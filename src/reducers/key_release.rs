@@ -0,0 +1,27 @@
+use {
+    crate::{
+        config::CONFIG,
+        state::State,
+        xlibwrapper::{action, util::keysym_lookup::into_mod},
+    },
+    reducer::*,
+};
+
+impl Reducer<action::KeyRelease> for State {
+    fn reduce(&mut self, action: action::KeyRelease) {
+        // Key releases are only seen while the keyboard is grabbed for CycleRecent
+        if self.window_switch.is_none() {
+            return;
+        }
+        let sym = match self.lib.keycode_to_key_sym(action.keycode as u8) {
+            Ok(sym) => sym,
+            Err(_) => return,
+        };
+        // Super_L, Alt_R and so on
+        let modifier = into_mod(sym.split('_').next().unwrap_or_default());
+        if modifier != 0 && CONFIG.mod_key & modifier == modifier {
+            debug!("Window switch committed on 0x{:x}", self.focus_w);
+            self.window_switch = None;
+        }
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(unused_imports)]
 use {
     crate::{
-        config::CONFIG,
+        config::{AppRule, RuleState, CONFIG},
         layout::LayoutTag,
         models::{
             monitor::Monitor, rect::*, window_type::WindowType, windowwrapper::*, WindowState,
@@ -21,81 +21,244 @@ use {
 
 impl Reducer<action::MapRequest> for State {
     fn reduce(&mut self, action: action::MapRequest) {
-        if let Some(_win) = self.lib.transient_for_hint(action.win) {
-            handle_transient_window(self, &action);
+        if wm::capture_scratchpad(self, action.win) {
             return;
         }
-        if self.lib.get_window_type(action.win) == WindowType::Dialog {
-            handle_transient_window(self, &action);
-            return;
+        // A window that asks for a workspace, or had one before a restart, goes there
+        // unless a rule picks the workspace
+        let desktop = self.lib.get_wm_desktop(action.win);
+        let ws = desktop.filter(|desktop| *desktop != ALL_DESKTOPS);
+        match matching_rule(self, action.win) {
+            Some(rule) => {
+                let ws = rule.workspace_tag().or(ws);
+                map_with_rule(self, &action, &rule, ws)
+            }
+            None if ws.is_some() => map_with_rule(self, &action, &AppRule::default(), ws),
+            None => map_window(self, &action),
+        }
+        if desktop == Some(ALL_DESKTOPS) {
+            wm::set_sticky(self, action.win, true);
         }
+    }
+}
 
-        debug!(
-            "MapRequest - window: {} - Parent: {}",
-            action.win, action.parent
-        );
+fn matching_rule(state: &State, w: Window) -> Option<AppRule> {
+    if CONFIG.rules.is_empty() {
+        return None;
+    }
+    let (class, instance) = match state.lib.get_class_hint(w) {
+        Ok((class, instance)) => (Some(class), Some(instance)),
+        Err(_) => (None, None),
+    };
+    let name = state.lib.get_window_name(w);
+    let window_type = state.lib.get_window_type(w);
+    CONFIG
+        .rules
+        .iter()
+        .find(|rule| {
+            rule.matches(
+                class.as_deref(),
+                instance.as_deref(),
+                name.as_deref(),
+                &window_type,
+            )
+        })
+        .cloned()
+}
 
-        if self.lib.get_window_type(action.win) == WindowType::Dock {
-            handle_dock(self, &action);
-            return;
+fn map_window(state: &mut State, action: &action::MapRequest) {
+    if let Some(_win) = state.lib.transient_for_hint(action.win) {
+        handle_transient_window(state, action);
+        return;
+    }
+    if state.lib.get_window_type(action.win) == WindowType::Dialog {
+        handle_transient_window(state, action);
+        return;
+    }
+
+    debug!(
+        "MapRequest - window: {} - Parent: {}",
+        action.win, action.parent
+    );
+
+    if state.lib.get_window_type(action.win) == WindowType::Dock {
+        handle_dock(state, action);
+        return;
+    }
+
+    if !state.lib.should_be_managed(action.win) {
+        return;
+    }
+
+    place_managed_window(state, action);
+}
+
+/// `ws` is the tag of the workspace the window goes to, the rule's own or the one it asked for
+fn map_with_rule(state: &mut State, action: &action::MapRequest, rule: &AppRule, ws: Option<u32>) {
+    debug!("MapRequest - window: {} - Rule: {:?}", action.win, rule);
+    if state.lib.get_window_type(action.win) == WindowType::Dock {
+        handle_dock(state, action);
+        return;
+    }
+
+    let (prev_mon, prev_focus) = (state.current_monitor, state.focus_w);
+    if let Some(mon_id) = rule.monitor {
+        if state.monitors.contains_key(&mon_id) {
+            state.current_monitor = mon_id;
+        } else {
+            warn!("Rule refers to a monitor that doesn't exist: {}", mon_id);
+        }
+    }
+
+    match rule.state {
+        Some(RuleState::Floating) => handle_transient_window(state, action),
+        Some(RuleState::Tiled) => {
+            if !state
+                .lib
+                .get_window_attributes(action.win)
+                .override_redirect
+            {
+                place_managed_window(state, action);
+            }
         }
+        _ => map_window(state, action),
+    }
 
-        if !self.lib.should_be_managed(action.win) {
+    let mon = match state.monitors.get_mut(&state.current_monitor) {
+        Some(mon) if mon.contains_window(action.win) => mon,
+        _ => {
+            state.current_monitor = prev_mon;
             return;
         }
+    };
 
-        let mon = self
-            .monitors
-            .get_mut(&self.current_monitor)
-            .expect("MapRequest: get_client_mut");
+    if let Some(geometry) = rule.geometry {
+        let screen = mon.screen.clone();
+        mon.swap_window(action.win, |mon, ww| {
+            if !ww.is_trans && mon.get_current_layout() != LayoutTag::Floating {
+                return ww;
+            }
+            let rect = Rect::new(
+                Position::new(screen.x + geometry.x, screen.y + geometry.y),
+                Size {
+                    width: geometry.width,
+                    height: geometry.height,
+                },
+            );
+            WindowWrapper {
+                window_rect: rect,
+                restore_position: rect.get_position(),
+                restore_size: rect.get_size(),
+                ..ww
+            }
+        });
+    }
 
-        if mon.contains_window(action.parent) {
-            self.lib.map_window(action.win);
-            self.lib.take_focus(action.win);
-            return;
+    match rule.state {
+        Some(RuleState::Maximized) => {
+            mon.swap_window(action.win, |mon, ww| match ww.current_state {
+                WindowState::Maximized => ww,
+                _ => wm::toggle_maximize(mon, ww),
+            });
         }
-        let windows = mon.place_window(action.win);
-        debug!(
-            "Windows in mon before place_window: {:?}",
-            mon.get_current_ws()
-                .unwrap()
-                .clients
-                .keys()
-                .collect::<Vec<&Window>>()
-        );
-        let window_amount = windows.len();
-        for (win, rect) in windows.into_iter() {
-            match mon.remove_window(win) {
-                Some(ww) => {
-                    if ww.current_state == WindowState::Maximized
-                        || ww.current_state == WindowState::Monocle
-                    {
-                        mon.add_window(
-                            win,
-                            WindowWrapper {
-                                window_rect: rect,
-                                previous_state: ww.current_state,
-                                current_state: WindowState::Free,
-                                ..ww
-                            },
-                        )
-                    } else {
-                        mon.add_window(
-                            win,
-                            WindowWrapper {
-                                window_rect: rect,
-                                current_state: WindowState::Free,
-                                ..ww
-                            },
-                        );
-                    };
-                }
-                None => {
-                    if win == action.win {
-                        debug!("Mapping window not already in mon");
-                        let ww = if window_amount == 1
-                            && mon.get_current_layout() != LayoutTag::Floating
-                        {
+        Some(RuleState::Monocle) => {
+            mon.swap_window(action.win, |mon, ww| match ww.current_state {
+                WindowState::Monocle => ww,
+                _ => wm::toggle_monocle(mon, ww),
+            });
+        }
+        _ => {}
+    }
+
+    let mut keep_focus = rule.skip_focus;
+    if let Some(ws) = ws {
+        if ws != mon.current_ws {
+            let is_tiled = mon.get_current_layout() != LayoutTag::Floating;
+            wm::move_to_ws(state, action.win, ws);
+            hide_unless_visible(state, action.win, ws);
+            if is_tiled {
+                wm::reorder(state);
+            }
+            // Never hand focus to a window on another workspace
+            keep_focus = true;
+        }
+    }
+
+    if keep_focus {
+        state.current_monitor = prev_mon;
+        state.focus_w = prev_focus;
+        if let Some(ws) = state
+            .monitors
+            .get_mut(&prev_mon)
+            .and_then(|mon| mon.get_current_ws_mut())
+        {
+            ws.focus_w = prev_focus;
+        }
+    }
+}
+
+fn hide_unless_visible(state: &mut State, w: Window, ws: u32) -> Option<()> {
+    let mon = state.monitors.get_mut(&wm::get_mon_by_ws(state, ws)?)?;
+    if mon.current_ws == ws {
+        return Some(());
+    }
+    mon.workspaces.get_mut(&ws)?.clients.get_mut(&w)?.hidden = true;
+    Some(())
+}
+
+fn place_managed_window(state: &mut State, action: &action::MapRequest) {
+    let scratchpads = state.scratchpads.values().copied().collect::<Vec<Window>>();
+    let mon = state
+        .monitors
+        .get_mut(&state.current_monitor)
+        .expect("MapRequest: get_client_mut");
+
+    if mon.contains_window(action.parent) {
+        state.lib.map_window(action.win);
+        state.lib.take_focus(action.win);
+        return;
+    }
+    let windows = mon.place_window(action.win);
+    debug!(
+        "Windows in mon before place_window: {:?}",
+        mon.get_current_ws()
+            .unwrap()
+            .clients
+            .keys()
+            .collect::<Vec<&Window>>()
+    );
+    let window_amount = windows.len();
+    for (win, rect) in windows.into_iter() {
+        match mon.remove_window(win) {
+            Some(ww) => {
+                if ww.current_state == WindowState::Maximized
+                    || ww.current_state == WindowState::Monocle
+                {
+                    mon.add_window(
+                        win,
+                        WindowWrapper {
+                            window_rect: rect,
+                            previous_state: ww.current_state,
+                            current_state: WindowState::Free,
+                            ..ww
+                        },
+                    )
+                } else {
+                    mon.add_window(
+                        win,
+                        WindowWrapper {
+                            window_rect: rect,
+                            current_state: WindowState::Free,
+                            ..ww
+                        },
+                    );
+                };
+            }
+            None => {
+                if win == action.win {
+                    debug!("Mapping window not already in mon");
+                    let ww =
+                        if window_amount == 1 && mon.get_current_layout() != LayoutTag::Floating {
                             let mut ww = WindowWrapper::new(action.win, rect, false);
                             ww.previous_state = WindowState::Maximized;
                             ww.current_state = WindowState::Maximized;
@@ -104,22 +267,22 @@ impl Reducer<action::MapRequest> for State {
                             WindowWrapper::new(action.win, rect, false)
                         };
 
-                        self.focus_w = action.win;
-                        mon.add_window(action.win, ww);
-                        mon.get_current_ws_mut().unwrap().focus_w = self.focus_w;
-                    }
+                    state.focus_w = action.win;
+                    mon.add_window(action.win, ww);
+                    mon.get_current_ws_mut().unwrap().focus_w = state.focus_w;
                 }
-            };
-        }
-        debug!("Windows in mon after place_window:");
-        mon.get_current_ws()
-            .unwrap()
-            .clients
-            .iter()
-            .for_each(|(key, val)| {
-                debug!("Window: {}, restore_pos: {:?}", key, val.restore_position);
-            });
+            }
+        };
     }
+    mon.hide_off_screen(&scratchpads);
+    debug!("Windows in mon after place_window:");
+    mon.get_current_ws()
+        .unwrap()
+        .clients
+        .iter()
+        .for_each(|(key, val)| {
+            debug!("Window: {}, restore_pos: {:?}", key, val.restore_position);
+        });
 }
 
 fn handle_transient_window(state: &mut State, action: &action::MapRequest) {
@@ -156,6 +319,10 @@ fn handle_transient_window(state: &mut State, action: &action::MapRequest) {
         }
         None => return,
     };
+    state.focus_w = action.win;
+    if let Some(ws) = mon.get_current_ws_mut() {
+        ws.focus_w = action.win;
+    }
     mon.add_window(
         action.win,
         WindowWrapper::new(
@@ -4,10 +4,14 @@ mod client_message_request;
 mod configure_request;
 mod destroy;
 mod enter_notify;
+mod expose;
+mod ipc_command;
 mod key_press;
+mod key_release;
 mod leave_notify;
 mod map_request;
 mod motion_notify;
 mod property_notify;
+mod reload_config;
 mod unknown;
 mod unmap_notify;
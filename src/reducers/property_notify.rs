@@ -19,5 +19,8 @@ impl Reducer<action::PropertyNotify> for State {
     fn reduce(&mut self, action: action::PropertyNotify) {
         let _name = self.lib.atom_name(action.atom).unwrap();
         //debug!("PropertyNotify, Atom name: {}", name);
+        if action.atom == xlib::XA_WM_NAME || action.atom == self.lib.xatom().NetWMName {
+            self.title_generation += 1;
+        }
     }
 }
@@ -0,0 +1,56 @@
+use {
+    crate::{
+        config::CONFIG,
+        layout::{self, LayoutTag},
+        state::State,
+        wm,
+        xlibwrapper::action,
+    },
+    reducer::*,
+};
+
+impl Reducer<action::ReloadConfig> for State {
+    fn reduce(&mut self, _action: action::ReloadConfig) {
+        reload_config(self);
+    }
+}
+
+pub(super) fn reload_config(state: &mut State) {
+    let master = (CONFIG.master_count, CONFIG.master_ratio);
+    // Layout scripts are picked up again even when the config itself is unchanged
+    match CONFIG.reload() {
+        Ok(true) => info!("Config reloaded"),
+        Ok(false) => debug!("Config unchanged"),
+        Err(e) => {
+            error!("Failed to reload config: {}", e);
+            return;
+        }
+    }
+    state.config_generation = CONFIG.generation();
+
+    layout::scripted::reload();
+    state
+        .monitors
+        .values_mut()
+        .flat_map(|mon| mon.workspaces.values_mut())
+        .for_each(|ws| ws.refresh_layouts());
+    // Counts changed with IncMasterCount are kept unless the config itself changed them
+    if master != (CONFIG.master_count, CONFIG.master_ratio) {
+        state
+            .monitors
+            .values_mut()
+            .flat_map(|mon| mon.workspaces.values_mut())
+            .for_each(|ws| ws.set_master(CONFIG.master_count, CONFIG.master_ratio));
+    }
+
+    // Gaps and borders might have changed so every visible tiled workspace is laid out again
+    let tiled = state
+        .monitors
+        .values()
+        .filter(|mon| mon.get_current_layout() != LayoutTag::Floating)
+        .map(|mon| mon.id)
+        .collect::<Vec<_>>();
+    for mon_id in tiled {
+        wm::reorder_monitor(state, mon_id);
+    }
+}
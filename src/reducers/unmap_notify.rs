@@ -4,6 +4,8 @@ use reducer::*;
 impl Reducer<action::UnmapNotify> for State {
     fn reduce(&mut self, action: action::UnmapNotify) {
         debug!("UnmapNotify");
+        // Minimized windows stay mapped, so the client withdrew it
+        wm::forget_minimized(self, action.win);
         let mon_id = wm::get_mon_by_window(&self, action.win).unwrap_or(self.current_monitor);
         let mon = self
             .monitors
@@ -29,6 +31,8 @@ impl Reducer<action::UnmapNotify> for State {
                 }
                 self.focus_w = next_focus;
                 mon.remove_window(action.win);
+                // Withdrawn windows lose their desktop, it is kept when hadlock exits
+                self.lib.set_wm_desktop(action.win, None);
             }
         }
         if mon.get_current_layout() != LayoutTag::Floating {
@@ -1,4 +1,5 @@
 use {
+    crate::config::CONFIG,
     crate::models::{monitor::Monitor, windowwrapper::WindowWrapper, workspace::Workspace},
     crate::xlibwrapper::{util::Position, xlibmodels::*, DisplayServer},
     derivative::*,
@@ -23,6 +24,24 @@ pub struct State {
     pub drag_start_pos: (i32, i32),
     pub drag_start_frame_pos: (i32, i32),
     pub drag_start_frame_size: (u32, u32),
+    pub config_generation: usize,
+    /// Bumped when a window title changes or a tab strip was exposed
+    pub title_generation: usize,
+    /// Windows captured for the scratchpads, by scratchpad name
+    pub scratchpads: HashMap<String, Window>,
+    /// Most recently focused first, kept up to date by the reactor
+    pub focus_history: RefCell<Vec<Window>>,
+    /// Set while `CycleRecent` walks the focus history
+    pub window_switch: Option<WindowSwitch>,
+    /// Whether the last ipc command could not be carried out, so the client gets an error
+    pub ipc_command_failed: bool,
+}
+
+/// Snapshot of the focus history taken when `CycleRecent` started
+#[derive(Debug, Clone)]
+pub struct WindowSwitch {
+    pub windows: Vec<Window>,
+    pub index: usize,
 }
 
 impl State {
@@ -63,6 +82,12 @@ impl State {
             drag_start_pos: (0, 0),
             drag_start_frame_pos: (0, 0),
             drag_start_frame_size: (0, 0),
+            config_generation: CONFIG.generation(),
+            title_generation: 0,
+            scratchpads: HashMap::new(),
+            focus_history: RefCell::new(vec![]),
+            window_switch: None,
+            ipc_command_failed: false,
         }
     }
 
@@ -80,6 +105,15 @@ impl State {
             .collect::<HashMap<u32, &Workspace>>()
     }
 
+    pub fn minimized(&self) -> HashMap<Window, &WindowWrapper> {
+        self.monitors
+            .values()
+            .flat_map(|mon| mon.workspaces.values())
+            .flat_map(|ws| ws.minimized.iter())
+            .map(|ww| (ww.window(), ww))
+            .collect()
+    }
+
     pub fn clients(&self) -> HashMap<Window, &WindowWrapper> {
         let client_vec = self
             .monitors
@@ -1,10 +1,17 @@
 use crate::{
-    config::Axis,
+    config::{Axis, Scratchpad, CONFIG},
     layout::LayoutTag,
-    models::{monitor::Monitor, rect::*, screen::*, windowwrapper::*, workspace::*, WindowState},
+    models::{
+        monitor::Monitor, rect::*, screen::*, window_type::WindowType, windowwrapper::*,
+        workspace::*, Direction, WindowState,
+    },
     state::State,
-    xlibwrapper::xlibmodels::*,
+    xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::*,
+    },
 };
+use std::process::Command;
 
 pub fn window_inside_screen(w_geom: &Geometry, screen: &Screen) -> bool {
     let inside_width = w_geom.x >= screen.x && w_geom.x < screen.x + screen.width;
@@ -93,14 +100,28 @@ pub fn get_mon_by_window(state: &State, w: Window) -> Option<MonitorId> {
 }
 
 pub fn resize_window(state: &mut State, w: Window, axis: &Axis, delta: i32) {
+    let scratchpads = state.scratchpads.values().copied().collect::<Vec<Window>>();
     let mon = state.monitors.get_mut(&state.current_monitor).unwrap();
     let windows = mon.resize_window(w, axis, delta);
     windows.into_iter().for_each(|ww| {
         mon.swap_window(ww.window(), |_, _| ww.clone());
     });
+    mon.hide_off_screen(&scratchpads);
 }
 
 pub fn set_current_ws(state: &mut State, ws: u32) -> Option<()> {
+    // Shown scratchpads follow along to the new workspace
+    let shown = shown_scratchpads(state);
+    let focus = state.focus_w;
+    let ret = switch_ws(state, ws);
+    for w in shown {
+        show_scratchpad(state, w, w == focus);
+    }
+    ret
+}
+
+fn switch_ws(state: &mut State, ws: u32) -> Option<()> {
+    let scratchpads = state.scratchpads.values().copied().collect::<Vec<Window>>();
     let mon = state.monitors.get_mut(&state.current_monitor)?;
     if mon.id == state.current_monitor && ws == mon.current_ws {
         return Some(());
@@ -128,6 +149,8 @@ pub fn set_current_ws(state: &mut State, ws: u32) -> Option<()> {
         state.current_monitor = mon.id;
         return Some(());
     }
+    // Sticky windows don't get hidden, they go along to the new workspace
+    let sticky = mon.take_sticky();
     mon.swap_ws(mon.current_ws, |_, mut ws| {
         ws.apply_to_all(|ww| {
             ww.hidden = true;
@@ -138,7 +161,8 @@ pub fn set_current_ws(state: &mut State, ws: u32) -> Option<()> {
     if mon.contains_ws(ws) {
         let mut new_ws = mon.remove_ws(ws)?;
         new_ws.apply_to_all(|ww| {
-            ww.hidden = false;
+            // Scratchpads left here stay hidden until they are toggled
+            ww.hidden = scratchpads.contains(&ww.window());
             ww.set_position(ww.get_position());
         });
         let win = new_ws.focus_w;
@@ -154,10 +178,16 @@ pub fn set_current_ws(state: &mut State, ws: u32) -> Option<()> {
     }
 
     state.current_monitor = mon.id;
-    if mon.workspaces.get(&mon.current_ws)?.clients.is_empty() && !mon.workspaces.is_empty() {
+    let old_ws = mon.workspaces.get(&mon.current_ws)?;
+    if old_ws.clients.is_empty() && old_ws.minimized.is_empty() && !mon.workspaces.is_empty() {
         mon.remove_ws(mon.current_ws);
     }
+    mon.push_ws_history(ws);
     mon.current_ws = ws;
+    for ww in sticky {
+        mon.add_window(ww.window(), ww);
+    }
+    mon.hide_off_screen(&scratchpads);
     state.current_monitor = mon.id;
     state.latest_cursor_pos = state.lib.pointer_pos(state.lib.get_root());
     Some(())
@@ -298,8 +328,56 @@ pub fn move_to_ws(state: &mut State, w: Window, ws: u32) -> Option<()> {
     Some(())
 }
 
+/// Moves a window to `ws` from whichever workspace it is on, without following it
+pub fn send_to_ws(state: &mut State, w: Window, ws: u32) -> Option<()> {
+    let root = state.lib.get_root();
+    let mon_id = get_mon_by_window(state, w)?;
+    let mon = state.monitors.get_mut(&mon_id)?;
+    let from = mon.get_ws_by_window(w)?;
+    if from == ws {
+        return Some(());
+    }
+    let shown_ws = mon.current_ws;
+    let tiled = from == shown_ws && mon.get_current_layout() != LayoutTag::Floating;
+
+    // move_to_ws moves away from the shown workspace of the current monitor
+    let prev_mon = state.current_monitor;
+    mon.current_ws = from;
+    state.current_monitor = mon_id;
+    move_to_ws(state, w, ws);
+    state.monitors.get_mut(&mon_id)?.current_ws = shown_ws;
+    state.current_monitor = prev_mon;
+
+    let mon = state.monitors.get_mut(&get_mon_by_ws(state, ws)?)?;
+    let shown = mon.current_ws == ws;
+    mon.workspaces.get_mut(&ws)?.clients.get_mut(&w)?.hidden = !shown;
+
+    if let Some(from_ws) = state.monitors.get_mut(&mon_id)?.workspaces.get_mut(&from) {
+        if from_ws.focus_w == w {
+            from_ws.focus_w = from_ws.get_newest().map_or(root, |(win, _)| *win);
+        }
+    }
+    if state.focus_w == w && !shown {
+        state.focus_w = state
+            .monitors
+            .get(&state.current_monitor)?
+            .get_current_ws()?
+            .focus_w;
+    }
+    if tiled {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
 pub fn reorder(state: &mut State) -> Option<()> {
-    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    reorder_monitor(state, state.current_monitor)
+}
+
+pub fn reorder_monitor(state: &mut State, mon_id: MonitorId) -> Option<()> {
+    let is_current = mon_id == state.current_monitor;
+    let scratchpads = state.scratchpads.values().copied().collect::<Vec<Window>>();
+    let mon = state.monitors.get_mut(&mon_id)?;
     debug!("reorder focus: {}", state.focus_w);
     let windows = mon
         .get_current_ws()?
@@ -309,7 +387,7 @@ pub fn reorder(state: &mut State) -> Option<()> {
         .cloned()
         .collect::<Vec<WindowWrapper>>();
 
-    if state.focus_w == state.lib.get_root() && !windows.is_empty() {
+    if is_current && state.focus_w == state.lib.get_root() && !windows.is_empty() {
         debug!("reorder focus is root");
         state.focus_w = *mon.get_newest()?.0;
     }
@@ -331,10 +409,513 @@ pub fn reorder(state: &mut State) -> Option<()> {
             ..ww
         });
     }
+    mon.hide_off_screen(&scratchpads);
     debug!("return from reorder");
     Some(())
 }
 
+/// Windows on the current workspace of `mon` that can take focus, in age order.
+/// Columns a scrolling layout hid are included since focusing them scrolls them back.
+pub fn focusable(mon: &Monitor) -> Vec<(Window, Rect)> {
+    let scrolling = mon.get_current_layout() == LayoutTag::Scrolling;
+    match mon.get_current_ws() {
+        Some(ws) => ws
+            .clients
+            .values()
+            .filter(|ww| !ww.hidden || (scrolling && !ww.is_trans))
+            .map(|ww| (ww.window(), ww.window_rect))
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Gives `w` on monitor `mon_id` focus from the keyboard, the cursor follows
+pub fn focus_window(state: &mut State, mon_id: MonitorId, w: Window) -> Option<()> {
+    let mon = state.monitors.get_mut(&mon_id)?;
+    mon.get_current_ws_mut()?.focus_w = w;
+    let scrolling = mon.get_current_layout() == LayoutTag::Scrolling;
+    state.focus_w = w;
+    state.current_monitor = mon_id;
+    state.mouse_follow.replace(true);
+    if scrolling {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
+pub fn cycle_focus(state: &mut State, forward: bool) -> Option<()> {
+    let windows = focusable(state.monitors.get(&state.current_monitor)?);
+    if windows.is_empty() {
+        return None;
+    }
+    let len = windows.len();
+    let index = match windows.iter().position(|(w, _)| *w == state.focus_w) {
+        Some(index) if forward => (index + 1) % len,
+        Some(index) => (index + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    focus_window(state, state.current_monitor, windows[index].0)
+}
+
+/// Focuses the nearest window in `direction`. If the current monitor has none
+/// the nearest monitor that way is tried, which gets focus even when it is empty.
+pub fn focus_direction(state: &mut State, direction: Direction) -> Option<()> {
+    let mon = state.monitors.get(&state.current_monitor)?;
+    let windows = focusable(mon);
+    let from = match windows.iter().find(|(w, _)| *w == state.focus_w) {
+        Some((_, rect)) => *rect,
+        None => {
+            let (pos, size): (Position, Size) = mon.screen.clone().into();
+            Rect::new(
+                Position::new(pos.x + size.width / 2, pos.y + size.height / 2),
+                Size {
+                    width: 0,
+                    height: 0,
+                },
+            )
+        }
+    };
+    let others = windows
+        .into_iter()
+        .filter(|(w, _)| *w != state.focus_w)
+        .collect::<Vec<(Window, Rect)>>();
+    if let Some(w) = nearest_in_direction(&from, &others, direction) {
+        return focus_window(state, state.current_monitor, w);
+    }
+
+    let mon_id = monitor_in_direction(state, direction)?;
+    let mon = state.monitors.get(&mon_id)?;
+    let windows = focusable(mon);
+    let w = nearest_in_direction(&from, &windows, direction)
+        .or_else(|| {
+            let focus = mon.get_current_ws()?.focus_w;
+            windows.iter().find(|(w, _)| *w == focus).map(|(w, _)| *w)
+        })
+        .or_else(|| windows.last().map(|(w, _)| *w))
+        .unwrap_or_else(|| state.lib.get_root());
+    focus_window(state, mon_id, w)
+}
+
+/// The monitor next to the current one in `direction`
+pub fn monitor_in_direction(state: &State, direction: Direction) -> Option<MonitorId> {
+    let screen_rect = |mon: &Monitor| {
+        let (pos, size): (Position, Size) = mon.screen.clone().into();
+        Rect::new(pos, size)
+    };
+    let screens = state
+        .monitors
+        .values()
+        .filter(|other| other.id != state.current_monitor)
+        .map(|other| (other.id, screen_rect(other)))
+        .collect::<Vec<(MonitorId, Rect)>>();
+    let current = state.monitors.get(&state.current_monitor)?;
+    nearest_in_direction(&screen_rect(current), &screens, direction)
+}
+
+/// Moves `w` to the workspace shown on monitor `mon_id`, focus goes along
+pub fn move_to_monitor(state: &mut State, w: Window, mon_id: MonitorId) -> Option<()> {
+    let from = state.current_monitor;
+    let ws = state.monitors.get(&mon_id)?.current_ws;
+    move_to_ws(state, w, ws)?;
+
+    let root = state.lib.get_root();
+    let mon = state.monitors.get_mut(&from)?;
+    let newest = mon.get_newest().map_or(root, |(newest, _)| *newest);
+    mon.get_current_ws_mut()?.focus_w = newest;
+    let tiled = |state: &State, mon_id| {
+        state
+            .monitors
+            .get(&mon_id)
+            .is_some_and(|mon| mon.get_current_layout() != LayoutTag::Floating)
+    };
+    if tiled(state, from) {
+        state.focus_w = newest;
+        reorder_monitor(state, from);
+    }
+    focus_window(state, mon_id, w)?;
+    if tiled(state, mon_id) {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
+/// The candidate whose center lies in `direction` from the center of `from`.
+/// Candidates overlapping `from` across the direction win, then the closest edge,
+/// then the one most in line with `from`.
+pub fn nearest_in_direction<T: Copy>(
+    from: &Rect,
+    candidates: &[(T, Rect)],
+    direction: Direction,
+) -> Option<T> {
+    let center = |rect: &Rect| {
+        let (pos, size) = (rect.get_position(), rect.get_size());
+        (pos.x + size.width / 2, pos.y + size.height / 2)
+    };
+    let span = |rect: &Rect, horizontal: bool| {
+        let (pos, size) = (rect.get_position(), rect.get_size());
+        if horizontal {
+            (pos.x, pos.x + size.width)
+        } else {
+            (pos.y, pos.y + size.height)
+        }
+    };
+    let (dx, dy) = match direction {
+        Direction::North => (0, -1),
+        Direction::NorthEast => (1, -1),
+        Direction::East => (1, 0),
+        Direction::SouthEast => (1, 1),
+        Direction::South => (0, 1),
+        Direction::SouthWest => (-1, 1),
+        Direction::West => (-1, 0),
+        Direction::NorthWest => (-1, -1),
+    };
+    let (from_x, from_y) = center(from);
+
+    candidates
+        .iter()
+        .filter(|(_, rect)| {
+            let (x, y) = center(rect);
+            (dx == 0 || (x - from_x).signum() == dx) && (dy == 0 || (y - from_y).signum() == dy)
+        })
+        .min_by_key(|(_, rect)| {
+            let (x, y) = center(rect);
+            if dx != 0 && dy != 0 {
+                return (0, 0, (x - from_x).pow(2) + (y - from_y).pow(2));
+            }
+            // Along the direction and across it
+            let horizontal = dx != 0;
+            let ((from_start, from_end), (start, end)) =
+                (span(from, horizontal), span(rect, horizontal));
+            let edge = if dx + dy > 0 {
+                start - from_end
+            } else {
+                from_start - end
+            };
+            let ((from_across_start, from_across_end), (across_start, across_end)) =
+                (span(from, !horizontal), span(rect, !horizontal));
+            let overlaps = across_start < from_across_end && from_across_start < across_end;
+            let offset = if horizontal { y - from_y } else { x - from_x };
+            (!overlaps as i32, edge.max(0), offset.abs())
+        })
+        .map(|(w, _)| *w)
+}
+
+/// Shows the scratchpad on the current workspace, or hides it if it is already shown there.
+/// The program is started if the scratchpad has no window yet.
+pub fn toggle_scratchpad(state: &mut State, name: &str) -> Option<()> {
+    let scratchpad = match CONFIG.scratchpads.iter().find(|s| s.name == name) {
+        Some(scratchpad) => scratchpad,
+        None => {
+            warn!("No scratchpad named: {}", name);
+            return None;
+        }
+    };
+    let w = match state.scratchpads.get(name) {
+        Some(w) if get_mon_by_window(state, *w).is_some() => *w,
+        _ => {
+            state.scratchpads.remove(name);
+            if let Err(e) = Command::new(&scratchpad.program)
+                .args(&scratchpad.args)
+                .spawn()
+            {
+                warn!("Failed to start scratchpad {}: {}", name, e);
+            }
+            return Some(());
+        }
+    };
+
+    let shown = state
+        .monitors
+        .get(&state.current_monitor)?
+        .get_client(w)
+        .is_some_and(|ww| !ww.hidden);
+    if shown {
+        hide_scratchpad(state, w)
+    } else {
+        show_scratchpad(state, w, true)
+    }
+}
+
+/// Takes a newly mapped window for the first scratchpad waiting for one with its class
+pub fn capture_scratchpad(state: &mut State, w: Window) -> bool {
+    let class = match state.lib.get_class_hint(w) {
+        Ok((class, _)) => class,
+        Err(_) => return false,
+    };
+    let scratchpad = CONFIG.scratchpads.iter().find(|s| {
+        s.class == class
+            && state
+                .scratchpads
+                .get(&s.name)
+                .is_none_or(|win| get_mon_by_window(state, *win).is_none())
+    });
+    let scratchpad = match scratchpad {
+        Some(scratchpad) => scratchpad,
+        None => return false,
+    };
+    debug!("Window 0x{:x} is scratchpad {}", w, scratchpad.name);
+    state.scratchpads.insert(scratchpad.name.clone(), w);
+
+    let mon = match state.monitors.get_mut(&state.current_monitor) {
+        Some(mon) => mon,
+        None => return false,
+    };
+    let rect = scratchpad_rect(&mon.screen, scratchpad);
+    mon.add_window(w, WindowWrapper::new(w, rect, true));
+    if let Some(ws) = mon.get_current_ws_mut() {
+        ws.focus_w = w;
+    }
+    state.focus_w = w;
+    true
+}
+
+fn shown_scratchpads(state: &State) -> Vec<Window> {
+    let mon = match state.monitors.get(&state.current_monitor) {
+        Some(mon) => mon,
+        None => return vec![],
+    };
+    state
+        .scratchpads
+        .values()
+        .filter(|w| mon.get_client(**w).is_some_and(|ww| !ww.hidden))
+        .copied()
+        .collect()
+}
+
+/// Moves the scratchpad to the current workspace, wherever it was, and centers it
+fn show_scratchpad(state: &mut State, w: Window, focus: bool) -> Option<()> {
+    let name = state
+        .scratchpads
+        .iter()
+        .find(|(_, win)| **win == w)
+        .map(|(name, _)| name.clone())?;
+    let scratchpad = CONFIG.scratchpads.iter().find(|s| s.name == name)?;
+
+    let mon = state.monitors.get_mut(&get_mon_by_window(state, w)?)?;
+    let ws = mon.get_ws_by_window(w)?;
+    let ww = mon.workspaces.get_mut(&ws)?.remove_window(w)?;
+
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    let rect = scratchpad_rect(&mon.screen, scratchpad);
+    mon.add_window(
+        w,
+        WindowWrapper {
+            window_rect: rect,
+            hidden: false,
+            is_trans: true,
+            ..ww
+        },
+    );
+    if focus {
+        mon.get_current_ws_mut()?.focus_w = w;
+        state.focus_w = w;
+    }
+    Some(())
+}
+
+fn hide_scratchpad(state: &mut State, w: Window) -> Option<()> {
+    let root = state.lib.get_root();
+    let mon = state.monitors.get_mut(&state.current_monitor)?;
+    mon.get_client_mut(w)?.hidden = true;
+
+    // Focus goes back to the newest window still shown
+    let ws = mon.get_current_ws_mut()?;
+    if ws.focus_w == w {
+        ws.focus_w = ws
+            .clients
+            .values()
+            .rev()
+            .find(|ww| !ww.hidden && ww.window() != w)
+            .map_or(root, |ww| ww.window());
+    }
+    if state.focus_w == w {
+        state.focus_w = ws.focus_w;
+    }
+    Some(())
+}
+
+/// Centered on `screen`, covering the configured share of it
+fn scratchpad_rect(screen: &Screen, scratchpad: &Scratchpad) -> Rect {
+    let size = Size {
+        width: (screen.width as f32 * scratchpad.width.clamp(0.1, 1.0)) as i32,
+        height: (screen.height as f32 * scratchpad.height.clamp(0.1, 1.0)) as i32,
+    };
+    Rect::new(
+        Position::new(
+            screen.x + (screen.width - size.width) / 2,
+            screen.y + (screen.height - size.height) / 2,
+        ),
+        size,
+    )
+}
+
+pub fn toggle_sticky(state: &mut State, w: Window) -> Option<()> {
+    let mon = state.monitors.get(&get_mon_by_window(state, w)?)?;
+    let sticky = !mon.get_client(w)?.sticky;
+    set_sticky(state, w, sticky)
+}
+
+/// Sticky windows float, unsticking puts windows that aren't dialogs back into the tiling
+pub fn set_sticky(state: &mut State, w: Window, sticky: bool) -> Option<()> {
+    let is_trans = sticky
+        || state.lib.transient_for_hint(w).is_some()
+        || state.lib.get_window_type(w) == WindowType::Dialog;
+    let mon_id = get_mon_by_window(state, w)?;
+    let mon = state.monitors.get_mut(&mon_id)?;
+    let ws = mon.get_ws_by_window(w)?;
+    let ww = mon.workspaces.get_mut(&ws)?.clients.get_mut(&w)?;
+    if ww.sticky == sticky {
+        return Some(());
+    }
+    ww.sticky = sticky;
+    ww.is_trans = is_trans;
+
+    let sticky_atom = state.lib.xatom().NetWMStateSticky;
+    let mut states = state.lib.get_window_states_atoms(w);
+    states.retain(|atom| *atom != sticky_atom);
+    if sticky {
+        states.push(sticky_atom);
+    }
+    state.lib.set_window_states_atoms(w, states);
+
+    if mon.get_current_layout() != LayoutTag::Floating {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
+/// Marks `w` for the reactor to close the way `Kill` does, minimized windows included
+pub fn close_window(state: &mut State, w: Window) -> Option<()> {
+    let ww = state
+        .monitors
+        .values_mut()
+        .flat_map(|mon| mon.workspaces.values_mut())
+        .find_map(|ws| {
+            if ws.clients.contains_key(&w) {
+                ws.clients.get_mut(&w)
+            } else {
+                ws.minimized.iter_mut().find(|ww| ww.window() == w)
+            }
+        })?;
+    ww.set_window_state(WindowState::Destroy);
+    Some(())
+}
+
+/// Brings the window into view wherever it is and focuses it, for pagers and taskbars
+pub fn activate_window(state: &mut State, w: Window) -> Option<()> {
+    if state.minimized().contains_key(&w) {
+        restore_minimized(state, w)?;
+    }
+    let ws = state
+        .monitors
+        .get(&get_mon_by_window(state, w)?)?
+        .get_ws_by_window(w)?;
+    set_current_ws(state, ws)?;
+
+    let hidden_scratchpad = state.scratchpads.values().any(|win| *win == w)
+        && state.clients().get(&w).is_some_and(|ww| ww.hidden);
+    if hidden_scratchpad {
+        show_scratchpad(state, w, true)?;
+    }
+    focus_window(state, get_mon_by_window(state, w)?, w)?;
+    state.lib.raise_window(w);
+    Some(())
+}
+
+/// Takes the window out of its workspace's layout and hides it until it is restored
+pub fn minimize(state: &mut State, w: Window) -> Option<()> {
+    let root = state.lib.get_root();
+    let mon_id = get_mon_by_window(state, w)?;
+    let mon = state.monitors.get_mut(&mon_id)?;
+    let tiled = mon.get_current_layout() != LayoutTag::Floating;
+    let ws = mon.workspaces.get_mut(&mon.get_ws_by_window(w)?)?;
+    let ww = ws.remove_window(w)?;
+    ws.minimized.push(WindowWrapper { hidden: true, ..ww });
+    if ws.focus_w == w {
+        ws.focus_w = ws
+            .clients
+            .values()
+            .rev()
+            .find(|ww| !ww.hidden)
+            .map_or(root, |ww| ww.window());
+    }
+    if state.focus_w == w {
+        state.focus_w = ws.focus_w;
+    }
+
+    state.lib.set_wm_state(w, ICONIC_STATE);
+    set_hidden_atom(state, w, true);
+    if tiled {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
+/// Puts a minimized window back on the workspace it was minimized on,
+/// it gets focus if that workspace is shown
+pub fn restore_minimized(state: &mut State, w: Window) -> Option<()> {
+    let (mon_id, tag) = state.monitors.values().find_map(|mon| {
+        mon.workspaces
+            .values()
+            .find(|ws| ws.minimized.iter().any(|ww| ww.window() == w))
+            .map(|ws| (mon.id, ws.tag))
+    })?;
+    let mon = state.monitors.get_mut(&mon_id)?;
+    let shown = tag == mon.current_ws;
+    let tiled = mon.get_current_layout() != LayoutTag::Floating;
+    let ws = mon.workspaces.get_mut(&tag)?;
+    let index = ws.minimized.iter().position(|ww| ww.window() == w)?;
+    let ww = ws.minimized.remove(index);
+    ws.add_window(
+        w,
+        WindowWrapper {
+            hidden: !shown,
+            ..ww
+        },
+    );
+    if shown {
+        ws.focus_w = w;
+        state.focus_w = w;
+        state.current_monitor = mon_id;
+    }
+
+    state.lib.set_wm_state(w, NORMAL_STATE);
+    set_hidden_atom(state, w, false);
+    if shown && tiled {
+        reorder_monitor(state, mon_id);
+    }
+    Some(())
+}
+
+/// The window went away while minimized
+pub fn forget_minimized(state: &mut State, w: Window) {
+    state
+        .monitors
+        .values_mut()
+        .flat_map(|mon| mon.workspaces.values_mut())
+        .for_each(|ws| ws.minimized.retain(|ww| ww.window() != w));
+}
+
+fn set_hidden_atom(state: &State, w: Window, hidden: bool) {
+    let hidden_atom = state.lib.xatom().NetWMStateHidden;
+    let mut states = state.lib.get_window_states_atoms(w);
+    states.retain(|atom| *atom != hidden_atom);
+    if hidden {
+        states.push(hidden_atom);
+    }
+    state.lib.set_window_states_atoms(w, states);
+}
+
+/// `_NET_FRAME_EXTENTS` as left, right, top and bottom, monocle and maximized windows have no border
+pub fn frame_extents(window_state: WindowState) -> [u32; 4] {
+    if window_state == WindowState::Monocle || window_state == WindowState::Maximized {
+        return [0; 4];
+    }
+    // Nothing draws a frame around clients yet, so only the border counts
+    [CONFIG.border_width as u32; 4]
+}
+
 pub fn point_is_inside(_state: &State, screen: &Screen, x: i32, y: i32) -> bool {
     let inside_height = y >= screen.y && y <= screen.y + screen.height as i32;
 
@@ -360,10 +941,13 @@ pub fn get_monitor_by_point(state: &State, x: i32, y: i32) -> MonitorId {
 mod test {
     use crate::models::{
         monitor::Monitor, rect::Rect, screen::Screen, windowwrapper::WindowWrapper,
-        workspace::Workspace, WindowState,
+        workspace::Workspace, Direction, WindowState,
     };
     use crate::wm;
-    use crate::xlibwrapper::xlibmodels::{Geometry, Window};
+    use crate::xlibwrapper::{
+        util::{Position, Size},
+        xlibmodels::{Geometry, Window},
+    };
 
     const ROOT: Window = 1;
     const SCREEN_1: Screen = Screen {
@@ -433,4 +1017,58 @@ mod test {
             original.get_size()
         );
     }
+
+    #[test]
+    fn scratchpad_is_centered() {
+        let scratchpad: crate::config::Scratchpad =
+            ron::de::from_str(r#"(name: "term", class: "dropdown", program: "xterm", width: 0.5)"#)
+                .unwrap();
+        let tested = wm::scratchpad_rect(&SCREEN_1, &scratchpad);
+        assert_eq!(
+            Rect::new(
+                Position::new(480, 216),
+                Size {
+                    width: 960,
+                    height: 648
+                }
+            ),
+            tested
+        );
+    }
+
+    #[test]
+    fn nearest_window_in_direction() {
+        let rect = |x, y, width, height| Rect::new(Position::new(x, y), Size { width, height });
+        // A master on the left and a stack of three on the right
+        let windows = vec![
+            (2, rect(500, 0, 500, 266)),
+            (3, rect(500, 266, 500, 266)),
+            (4, rect(500, 532, 500, 268)),
+        ];
+        let master = rect(0, 0, 500, 800);
+        assert_eq!(
+            Some(3),
+            wm::nearest_in_direction(&master, &windows, Direction::East)
+        );
+        assert_eq!(
+            None,
+            wm::nearest_in_direction(&master, &windows, Direction::West)
+        );
+        assert_eq!(
+            Some(2),
+            wm::nearest_in_direction(&windows[1].1, &windows, Direction::North)
+        );
+        let windows = vec![(1, master), (2, windows[0].1)];
+        assert_eq!(
+            Some(1),
+            wm::nearest_in_direction(&rect(500, 532, 500, 268), &windows, Direction::West)
+        );
+    }
+
+    #[test]
+    fn frame_extents_follow_border() {
+        let border = crate::config::CONFIG.border_width as u32;
+        assert_eq!([border; 4], wm::frame_extents(WindowState::Free));
+        assert_eq!([0; 4], wm::frame_extents(WindowState::Monocle));
+    }
 }
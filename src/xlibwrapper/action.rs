@@ -1,6 +1,7 @@
 #![allow(unused_variables, dead_code)]
 
 use super::xlibmodels::*;
+use crate::config::KeyEffect;
 
 pub struct ConfigurationNotification {
     pub win: Window,
@@ -92,8 +93,15 @@ pub struct Focus {
     pub win: Window,
 }
 
+pub struct IpcCommand {
+    pub effect: KeyEffect,
+    pub workspace: Option<u32>,
+}
+
 pub struct UpdateLayout;
 
+pub struct ReloadConfig;
+
 pub struct ButtonReleased;
 
 pub struct UnknownEvent;
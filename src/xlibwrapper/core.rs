@@ -2,6 +2,7 @@
 use std::ffi::CString;
 use std::mem::{self, MaybeUninit};
 use std::os::raw::*;
+use std::os::unix::io::RawFd;
 use std::ptr::null;
 pub use x11_dl::xlib;
 
@@ -11,7 +12,7 @@ use super::cursor::Cursor;
 use super::util::Position;
 use crate::config::*;
 
-use crate::models::{dockarea::DockArea, screen::Screen, window_type::WindowType};
+use crate::models::{dockarea::DockArea, rect::Rect, screen::Screen, window_type::WindowType};
 
 pub(crate) unsafe extern "C" fn error_handler(
     _: *mut xlib::Display,
@@ -43,6 +44,7 @@ pub struct XlibWrapper {
     cursors: Cursor,
 }
 
+#[allow(clippy::new_without_default)]
 impl XlibWrapper {
     pub fn new() -> Self {
         let (disp, root, lib, xatom, cursors) = unsafe {
@@ -400,23 +402,29 @@ impl DisplayServer for XlibWrapper {
     }
 
     fn init_desktops_hints(&self) {
-        //set the number of desktop
-        let data = vec![CONFIG.workspaces.len() as u32];
-        //let num_ws = self.get_screens().len();
-        //let data = vec![num_ws as u32];
-        self.set_desktop_prop(&data, self.xatom.NetNumberOfDesktops);
+        self.set_desktop_names(&desktop_names(&CONFIG.workspaces));
         //set a current desktop
         let data = vec![0 as u32, xlib::CurrentTime as u32];
         self.set_desktop_prop(&data, self.xatom.NetCurrentDesktop);
+        self.support_wm_check();
+
+        //set a viewport
+        let data = vec![0 as u32, 0 as u32];
+        self.set_desktop_prop(&data, self.xatom.NetDesktopViewport);
+    }
+
+    fn set_desktop_names(&self, names: &[String]) {
+        //set the number of desktop
+        let data = vec![names.len() as u32];
+        self.set_desktop_prop(&data, self.xatom.NetNumberOfDesktops);
         //set desktop names
         let mut text: xlib::XTextProperty = unsafe { mem::uninitialized() };
         unsafe {
-            let mut clist_tags: Vec<*mut c_char> = CONFIG
-                .workspaces
-                .values()
+            let mut clist_tags: Vec<*mut c_char> = names
+                .iter()
                 .map(|x| {
                     CString::new(x.to_string())
-                        .expect("xlibwrapper::core: init_desktops_hints")
+                        .expect("xlibwrapper::core: set_desktop_names")
                         .into_raw()
                 })
                 .collect();
@@ -435,12 +443,7 @@ impl DisplayServer for XlibWrapper {
                 &mut text,
                 self.xatom.NetDesktopNames,
             );
-            self.support_wm_check();
         }
-
-        //set a viewport
-        let data = vec![0 as u32, 0 as u32];
-        self.set_desktop_prop(&data, self.xatom.NetDesktopViewport);
     }
 
     fn get_window_states_atoms(&self, window: xlib::Window) -> Vec<xlib::Atom> {
@@ -477,7 +480,7 @@ impl DisplayServer for XlibWrapper {
     }
 
     fn set_window_states_atoms(&self, window: xlib::Window, states: Vec<xlib::Atom>) {
-        let data: Vec<u32> = states.iter().map(|x| *x as u32).collect();
+        // Format 32 properties are passed as longs
         unsafe {
             (self.lib.XChangeProperty)(
                 self.display,
@@ -486,10 +489,83 @@ impl DisplayServer for XlibWrapper {
                 xlib::XA_ATOM,
                 32,
                 xlib::PropModeReplace,
+                states.as_ptr() as *const u8,
+                states.len() as i32,
+            );
+        }
+    }
+
+    fn set_wm_state(&self, window: xlib::Window, state: i32) {
+        let data: [c_long; 2] = [state as c_long, 0];
+        unsafe {
+            (self.lib.XChangeProperty)(
+                self.display,
+                window,
+                self.xatom.WMState,
+                self.xatom.WMState,
+                32,
+                xlib::PropModeReplace,
                 data.as_ptr() as *const u8,
                 data.len() as i32,
             );
-            std::mem::forget(data);
+        }
+    }
+
+    fn set_wm_desktop(&self, window: xlib::Window, desktop: Option<u32>) {
+        unsafe {
+            match desktop {
+                Some(desktop) => {
+                    let data: [c_long; 1] = [desktop as c_long];
+                    (self.lib.XChangeProperty)(
+                        self.display,
+                        window,
+                        self.xatom.NetWMDesktop,
+                        xlib::XA_CARDINAL,
+                        32,
+                        xlib::PropModeReplace,
+                        data.as_ptr() as *const u8,
+                        1,
+                    );
+                }
+                None => {
+                    (self.lib.XDeleteProperty)(self.display, window, self.xatom.NetWMDesktop);
+                }
+            }
+        }
+    }
+
+    fn get_wm_desktop(&self, window: xlib::Window) -> Option<u32> {
+        let mut format_return: i32 = 0;
+        let mut nitems_return: c_ulong = 0;
+        let mut bytes_after_return: c_ulong = 0;
+        let mut type_return: xlib::Atom = 0;
+        let mut prop_return: *mut c_uchar = std::ptr::null_mut();
+        unsafe {
+            let status = (self.lib.XGetWindowProperty)(
+                self.display,
+                window,
+                self.xatom.NetWMDesktop,
+                0,
+                1,
+                xlib::False,
+                xlib::XA_CARDINAL,
+                &mut type_return,
+                &mut format_return,
+                &mut nitems_return,
+                &mut bytes_after_return,
+                &mut prop_return,
+            );
+            if status != i32::from(xlib::Success) || prop_return.is_null() {
+                return None;
+            }
+            #[allow(clippy::cast_ptr_alignment)]
+            let desktop = if nitems_return == 1 {
+                Some(*(prop_return as *const c_long) as u32)
+            } else {
+                None
+            };
+            (self.lib.XFree)(prop_return as *mut c_void);
+            desktop
         }
     }
 
@@ -543,7 +619,15 @@ impl DisplayServer for XlibWrapper {
                 xlib::RevertToPointerRoot,
                 xlib::CurrentTime,
             );
-            let list = vec![w];
+            self.flush();
+        }
+        //self.send_xevent_atom(w, self.xatom.WMTakeFocus);
+        self.sync(false);
+    }
+
+    fn set_active_window(&self, w: Option<Window>) {
+        let list: [Window; 1] = [w.unwrap_or(0)];
+        unsafe {
             (self.lib.XChangeProperty)(
                 self.display,
                 self.root,
@@ -554,11 +638,7 @@ impl DisplayServer for XlibWrapper {
                 list.as_ptr() as *const u8,
                 1,
             );
-            mem::forget(list);
-            self.flush();
         }
-        //self.send_xevent_atom(w, self.xatom.WMTakeFocus);
-        self.sync(false);
     }
 
     fn set_window_background_color(&self, w: Window, color: Color) {
@@ -650,6 +730,33 @@ impl DisplayServer for XlibWrapper {
         }
     }
 
+    fn modifier_state(&self) -> u32 {
+        unsafe {
+            let mut root_return = 0;
+            let mut child_return = 0;
+            let mut root_x = 0i32;
+            let mut root_y = 0i32;
+            let mut win_x = 0i32;
+            let mut win_y = 0i32;
+            let mut mask = 0u32;
+            if (self.lib.XQueryPointer)(
+                self.display,
+                self.root,
+                &mut root_return,
+                &mut child_return,
+                &mut root_x,
+                &mut root_y,
+                &mut win_x,
+                &mut win_y,
+                &mut mask,
+            ) == 0
+            {
+                warn!("Query pointer retured false")
+            }
+            mask
+        }
+    }
+
     fn move_cursor(&self, pos: Position) {
         unsafe {
             (self.lib.XWarpPointer)(self.display, 0, self.root, 0, 0, 0, 0, pos.x, pos.y);
@@ -723,6 +830,66 @@ impl DisplayServer for XlibWrapper {
         }
     }
 
+    fn update_net_client_list_stacking(&self, clients: &[Window]) {
+        // Children of the root are listed bottom to top
+        let stacking = self
+            .get_top_level_windows()
+            .into_iter()
+            .filter(|w| clients.contains(w))
+            .collect::<Vec<Window>>();
+        unsafe {
+            (self.lib.XChangeProperty)(
+                self.display,
+                self.root,
+                self.xatom.NetClientListStacking,
+                xlib::XA_WINDOW,
+                32,
+                xlib::PropModeReplace,
+                stacking.as_ptr() as *const u8,
+                stacking.len() as i32,
+            );
+        }
+    }
+
+    fn set_workarea(&self, areas: &[Rect]) {
+        let data = areas
+            .iter()
+            .flat_map(|area| {
+                let (pos, size) = (area.get_position(), area.get_size());
+                vec![pos.x, pos.y, size.width, size.height]
+            })
+            .map(|x| x as c_long)
+            .collect::<Vec<c_long>>();
+        unsafe {
+            (self.lib.XChangeProperty)(
+                self.display,
+                self.root,
+                self.xatom.NetWorkarea,
+                xlib::XA_CARDINAL,
+                32,
+                xlib::PropModeReplace,
+                data.as_ptr() as *const u8,
+                data.len() as i32,
+            );
+        }
+    }
+
+    fn set_frame_extents(&self, w: Window, extents: [u32; 4]) {
+        let data = extents.map(|x| x as c_long);
+        unsafe {
+            (self.lib.XChangeProperty)(
+                self.display,
+                w,
+                self.xatom.NetFrameExtents,
+                xlib::XA_CARDINAL,
+                32,
+                xlib::PropModeReplace,
+                data.as_ptr() as *const u8,
+                data.len() as i32,
+            );
+        }
+    }
+
     fn create_simple_window(
         &self,
         w: Window,
@@ -791,9 +958,28 @@ impl DisplayServer for XlibWrapper {
         }
     }
 
-    fn ungrab_keys(&self, _w: Window) {
+    fn ungrab_keys(&self, w: Window) {
         unsafe {
-            (self.lib.XUngrabKey)(self.display, xlib::AnyKey, xlib::AnyModifier, self.root);
+            (self.lib.XUngrabKey)(self.display, xlib::AnyKey, xlib::AnyModifier, w);
+        }
+    }
+
+    fn grab_keyboard(&self, w: Window) {
+        unsafe {
+            (self.lib.XGrabKeyboard)(
+                self.display,
+                w,
+                xlib::False,
+                xlib::GrabModeAsync,
+                xlib::GrabModeAsync,
+                xlib::CurrentTime,
+            );
+        }
+    }
+
+    fn ungrab_keyboard(&self) {
+        unsafe {
+            (self.lib.XUngrabKeyboard)(self.display, xlib::CurrentTime);
         }
     }
 
@@ -883,6 +1069,51 @@ impl DisplayServer for XlibWrapper {
         }
     }
 
+    fn get_window_name(&self, w: Window) -> Option<String> {
+        let mut format_return: i32 = 0;
+        let mut nitems_return: c_ulong = 0;
+        let mut bytes_after_return: c_ulong = 0;
+        let mut type_return: xlib::Atom = 0;
+        let mut prop_return: *mut c_uchar = std::ptr::null_mut();
+        unsafe {
+            let status = (self.lib.XGetWindowProperty)(
+                self.display,
+                w,
+                self.xatom.NetWMName,
+                0,
+                1024,
+                xlib::False,
+                self.xatom.NetUtf8String,
+                &mut type_return,
+                &mut format_return,
+                &mut nitems_return,
+                &mut bytes_after_return,
+                &mut prop_return,
+            );
+            if status == i32::from(xlib::Success) && !prop_return.is_null() {
+                let bytes = std::slice::from_raw_parts(prop_return, nitems_return as usize);
+                let name = String::from_utf8_lossy(bytes).into_owned();
+                (self.lib.XFree)(prop_return as *mut c_void);
+                if !name.is_empty() {
+                    return Some(name);
+                }
+            }
+
+            // Fall back to WM_NAME for clients not setting _NET_WM_NAME
+            let mut name_return: *mut c_char = std::ptr::null_mut();
+            if (self.lib.XFetchName)(self.display, w, &mut name_return) != 0
+                && !name_return.is_null()
+            {
+                let name = std::ffi::CStr::from_ptr(name_return)
+                    .to_string_lossy()
+                    .into_owned();
+                (self.lib.XFree)(name_return as *mut c_void);
+                return Some(name);
+            }
+            None
+        }
+    }
+
     fn get_atom_prop_value(&self, window: xlib::Window, prop: xlib::Atom) -> Option<xlib::Atom> {
         // Shamelessly stolen from lex148/leftWM
         let mut format_return: i32 = 0;
@@ -992,6 +1223,64 @@ impl DisplayServer for XlibWrapper {
         }
     }
 
+    fn connection_fd(&self) -> RawFd {
+        unsafe { (self.lib.XConnectionNumber)(self.display) }
+    }
+
+    fn pending_events(&self) -> u32 {
+        unsafe { (self.lib.XPending)(self.display) as u32 }
+    }
+
+    fn fill_rect(&self, w: Window, rect: Rect, color: Color) {
+        let (pos, size) = (rect.get_position(), rect.get_size());
+        unsafe {
+            let gc = (self.lib.XCreateGC)(self.display, w, 0, std::ptr::null_mut());
+            (self.lib.XSetForeground)(self.display, gc, color.value());
+            (self.lib.XFillRectangle)(
+                self.display,
+                w,
+                gc,
+                pos.x,
+                pos.y,
+                size.width.max(0) as u32,
+                size.height.max(0) as u32,
+            );
+            (self.lib.XFreeGC)(self.display, gc);
+        }
+    }
+
+    fn draw_text(&self, w: Window, pos: Position, text: &str, color: Color) {
+        // The core font only knows latin1
+        let text = text
+            .chars()
+            .map(|c| {
+                if c.is_ascii() && !c.is_ascii_control() {
+                    c
+                } else {
+                    '?'
+                }
+            })
+            .collect::<String>();
+        let text = match CString::new(text) {
+            Ok(text) => text,
+            Err(_) => return,
+        };
+        unsafe {
+            let gc = (self.lib.XCreateGC)(self.display, w, 0, std::ptr::null_mut());
+            (self.lib.XSetForeground)(self.display, gc, color.value());
+            (self.lib.XDrawString)(
+                self.display,
+                w,
+                gc,
+                pos.x,
+                pos.y,
+                text.as_ptr(),
+                text.as_bytes().len() as i32,
+            );
+            (self.lib.XFreeGC)(self.display, gc);
+        }
+    }
+
     fn raise_window(&self, w: Window) {
         unsafe {
             (self.lib.XRaiseWindow)(self.display, w);
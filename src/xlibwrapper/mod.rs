@@ -2,7 +2,7 @@
 
 pub mod action;
 pub mod core;
-pub mod cursor;
+mod cursor;
 pub mod masks;
 pub mod mock_core;
 pub mod util;
@@ -10,8 +10,9 @@ pub mod xatom;
 pub mod xlibmodels;
 
 use {
-    super::models::{dockarea::DockArea, screen::Screen, window_type::WindowType},
+    super::models::{dockarea::DockArea, rect::Rect, screen::Screen, window_type::WindowType},
     std::os::raw::*,
+    std::os::unix::io::RawFd,
     util::Position,
     util::*,
     x11_dl::xlib,
@@ -32,6 +33,11 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Sets the number of desktops and their names, indexed by workspace tag
+    fn set_desktop_names(&self, _names: &[String]) {
+        unimplemented!()
+    }
+
     fn get_window_states_atoms(&self, _window: xlib::Window) -> Vec<xlib::Atom> {
         unimplemented!()
     }
@@ -40,6 +46,20 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Sets the ICCCM `WM_STATE`, `NORMAL_STATE` or `ICONIC_STATE`
+    fn set_wm_state(&self, _window: xlib::Window, _state: i32) {
+        unimplemented!()
+    }
+
+    /// Sets `_NET_WM_DESKTOP` on a client, `None` removes it
+    fn set_wm_desktop(&self, _window: xlib::Window, _desktop: Option<u32>) {
+        unimplemented!()
+    }
+
+    fn get_wm_desktop(&self, _window: xlib::Window) -> Option<u32> {
+        unimplemented!()
+    }
+
     fn set_desktop_prop_u64(&self, _value: u64, _atom: c_ulong, _type_: c_ulong) {
         unimplemented!()
     }
@@ -60,6 +80,11 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Sets `_NET_ACTIVE_WINDOW` on the root, `None` when no window has focus
+    fn set_active_window(&self, _w: Option<Window>) {
+        unimplemented!()
+    }
+
     fn set_window_background_color(&self, _w: Window, _color: Color) {
         unimplemented!()
     }
@@ -76,6 +101,11 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Modifier and button mask as the server currently sees it
+    fn modifier_state(&self) -> u32 {
+        unimplemented!()
+    }
+
     fn move_cursor(&self, _pos: Position) {
         unimplemented!()
     }
@@ -96,6 +126,21 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Publishes `clients` as `_NET_CLIENT_LIST_STACKING`, in the stacking order of the server
+    fn update_net_client_list_stacking(&self, _clients: &[Window]) {
+        unimplemented!()
+    }
+
+    /// Sets `_NET_WORKAREA`, one area per desktop
+    fn set_workarea(&self, _areas: &[Rect]) {
+        unimplemented!()
+    }
+
+    /// Sets `_NET_FRAME_EXTENTS` as left, right, top and bottom
+    fn set_frame_extents(&self, _w: Window, _extents: [u32; 4]) {
+        unimplemented!()
+    }
+
     fn create_simple_window(
         &self,
         _w: Window,
@@ -175,6 +220,10 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    fn get_window_name(&self, _w: Window) -> Option<String> {
+        unimplemented!()
+    }
+
     fn get_atom_prop_value(&self, _window: xlib::Window, _prop: xlib::Atom) -> Option<xlib::Atom> {
         unimplemented!()
     }
@@ -183,6 +232,15 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    /// Every key event goes to `w` until `ungrab_keyboard`, releases included
+    fn grab_keyboard(&self, _w: Window) {
+        unimplemented!()
+    }
+
+    fn ungrab_keyboard(&self) {
+        unimplemented!()
+    }
+
     fn grab_key(
         &self,
         _key_code: u32,
@@ -211,6 +269,22 @@ pub trait DisplayServer {
         unimplemented!()
     }
 
+    fn connection_fd(&self) -> RawFd {
+        unimplemented!()
+    }
+
+    fn pending_events(&self) -> u32 {
+        unimplemented!()
+    }
+
+    fn fill_rect(&self, _w: Window, _rect: Rect, _color: Color) {
+        unimplemented!()
+    }
+
+    fn draw_text(&self, _w: Window, _pos: Position, _text: &str, _color: Color) {
+        unimplemented!()
+    }
+
     fn raise_window(&self, _w: Window) {
         unimplemented!()
     }
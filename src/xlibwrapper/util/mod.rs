@@ -91,7 +91,7 @@ impl std::ops::Sub for Size {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub enum Color {
     Red,
     Blue,
@@ -104,6 +104,7 @@ pub enum Color {
     DefaultBackground,
     DefaultFocusedBackground,
     DefaultBorder,
+    DefaultTabText,
     #[serde(deserialize_with = "color_deserialize")]
     Custom(u64),
 }
@@ -137,6 +138,7 @@ impl Color {
             Color::DefaultBackground => 0x005A_3C85,
             Color::DefaultFocusedBackground => 0x009E_416D,
             Color::DefaultBorder => 0x0094_c507,
+            Color::DefaultTabText => 0x00ff_ffff,
             Color::Custom(value) => value,
         }
     }
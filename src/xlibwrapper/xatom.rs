@@ -9,9 +9,11 @@ pub struct XAtom {
     pub WMProtocols: xlib::Atom,
     pub WMDelete: xlib::Atom,
     pub WMState: xlib::Atom,
+    pub WMChangeState: xlib::Atom,
     pub WMTakeFocus: xlib::Atom,
     pub Window: xlib::Atom,
     pub NetActiveWindow: xlib::Atom,
+    pub NetCloseWindow: xlib::Atom,
     pub NetSupported: xlib::Atom,
     pub NetWMName: xlib::Atom,
     pub NetWMState: xlib::Atom,
@@ -40,6 +42,10 @@ pub struct XAtom {
 
     pub NetSupportingWmCheck: xlib::Atom,
     pub NetClientList: xlib::Atom,
+    pub NetClientListStacking: xlib::Atom,
+    pub NetWorkarea: xlib::Atom,
+    pub NetFrameExtents: xlib::Atom,
+    pub NetRequestFrameExtents: xlib::Atom,
     pub NetDesktopViewport: xlib::Atom,
     pub NetNumberOfDesktops: xlib::Atom,
     pub NetCurrentDesktop: xlib::Atom,
@@ -57,17 +63,18 @@ impl XAtom {
     pub fn net_supported(&self) -> Vec<xlib::Atom> {
         vec![
             self.NetActiveWindow,
+            self.NetCloseWindow,
             self.NetSupported,
             self.NetWMName,
             self.NetWMState,
             //self.NetWMStateModal,
-            //self.NetWMStateSticky,
+            self.NetWMStateSticky,
             //self.NetWMStateMaximizedVert,
             //self.NetWMStateMaximizedHorz,
             //self.NetWMStateShaded,
             //self.NetWMStateSkipTaskbar,
             //self.NetWMStateSkipPager,
-            //self.NetWMStateHidden,
+            self.NetWMStateHidden,
             self.NetWMStateFullscreen,
             //self.NetWMStateAbove,
             //self.NetWMStateBelow,
@@ -82,6 +89,10 @@ impl XAtom {
             self.NetWMWindowTypeDialog,
             self.NetSupportingWmCheck,
             self.NetClientList,
+            self.NetClientListStacking,
+            self.NetWorkarea,
+            self.NetFrameExtents,
+            self.NetRequestFrameExtents,
             self.NetDesktopViewport,
             self.NetNumberOfDesktops,
             self.NetCurrentDesktop,
@@ -102,6 +113,9 @@ impl XAtom {
         if atom == self.WMState {
             return "WM_STATE";
         }
+        if atom == self.WMChangeState {
+            return "WM_CHANGE_STATE";
+        }
         if atom == self.WMTakeFocus {
             return "WM_TAKE_FOCUS";
         }
@@ -111,6 +125,9 @@ impl XAtom {
         if atom == self.NetActiveWindow {
             return "_NET_ACTIVE_WINDOW";
         }
+        if atom == self.NetCloseWindow {
+            return "_NET_CLOSE_WINDOW";
+        }
         if atom == self.NetSupported {
             return "_NET_SUPPORTED";
         }
@@ -166,6 +183,18 @@ impl XAtom {
         if atom == self.NetWMWindowTypeDock {
             return "_NET_WM_WINDOW_TYPE_DOCK";
         }
+        if atom == self.NetClientListStacking {
+            return "_NET_CLIENT_LIST_STACKING";
+        }
+        if atom == self.NetWorkarea {
+            return "_NET_WORKAREA";
+        }
+        if atom == self.NetFrameExtents {
+            return "_NET_FRAME_EXTENTS";
+        }
+        if atom == self.NetRequestFrameExtents {
+            return "_NET_REQUEST_FRAME_EXTENTS";
+        }
         if atom == self.NetClientList {
             return "_NET_CLIENT_LIST";
         }
@@ -204,8 +233,10 @@ impl XAtom {
             WMProtocols: from(xlib, dpy, "WM_PROTOCOLS"),
             WMDelete: from(xlib, dpy, "WM_DELETE_WINDOW"),
             WMState: from(xlib, dpy, "WM_STATE"),
+            WMChangeState: from(xlib, dpy, "WM_CHANGE_STATE"),
             WMTakeFocus: from(xlib, dpy, "WM_TAKE_FOCUS"),
             NetActiveWindow: from(xlib, dpy, "_NET_ACTIVE_WINDOW"),
+            NetCloseWindow: from(xlib, dpy, "_NET_CLOSE_WINDOW"),
             NetSupported: from(xlib, dpy, "_NET_SUPPORTED"),
             NetWMName: from(xlib, dpy, "_NET_WM_NAME"),
             Window: from(xlib, dpy, "WINDOW"),
@@ -235,6 +266,10 @@ impl XAtom {
             NetSupportingWmCheck: from(xlib, dpy, "_NET_SUPPORTING_WM_CHECK"),
 
             NetClientList: from(xlib, dpy, "_NET_CLIENT_LIST"),
+            NetClientListStacking: from(xlib, dpy, "_NET_CLIENT_LIST_STACKING"),
+            NetWorkarea: from(xlib, dpy, "_NET_WORKAREA"),
+            NetFrameExtents: from(xlib, dpy, "_NET_FRAME_EXTENTS"),
+            NetRequestFrameExtents: from(xlib, dpy, "_NET_REQUEST_FRAME_EXTENTS"),
             NetDesktopViewport: from(xlib, dpy, "_NET_DESKTOP_VIEWPORT"),
             NetNumberOfDesktops: from(xlib, dpy, "_NET_NUMBER_OF_DESKTOPS"),
             NetCurrentDesktop: from(xlib, dpy, "_NET_CURRENT_DESKTOP"),
@@ -10,6 +10,13 @@ pub(crate) type Time = xlib::Time;
 pub(crate) type KeyCode = xlib::KeyCode;
 pub(crate) type MonitorId = u32;
 
+/// ICCCM `WM_STATE` values
+pub(crate) const NORMAL_STATE: i32 = 1;
+pub(crate) const ICONIC_STATE: i32 = 3;
+
+/// `_NET_WM_DESKTOP` of windows shown on every desktop
+pub(crate) const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct WindowChanges {
     pub x: i32,